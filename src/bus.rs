@@ -0,0 +1,110 @@
+mod map;
+
+use std::cell::RefCell;
+use std::ops::{Index, IndexMut};
+use std::rc::Rc;
+
+pub use map::*;
+
+// Everything the CPU reads or writes goes through a Bus, which lets
+// memory-mapped devices, ROM and banking sit behind the address space
+pub trait Bus {
+    fn read(&mut self, addr: u16) -> u8;
+    fn write(&mut self, addr: u16, value: u8);
+
+    // The address of an access the bus couldn't complete since it was last
+    // asked, for buses that can fail one. The CPU asks after every
+    // instruction and only stops on it under the strict error policy
+    fn take_fault(&mut self) -> Option<u16> {
+        None
+    }
+}
+
+// Lets a device be shared, so it can sit in a memory map while the
+// rest of the emulator keeps a handle to it
+impl<T: Bus> Bus for Rc<RefCell<T>> {
+    fn read(&mut self, addr: u16) -> u8 {
+        self.borrow_mut().read(addr)
+    }
+
+    fn write(&mut self, addr: u16, value: u8) {
+        self.borrow_mut().write(addr, value);
+    }
+
+    fn take_fault(&mut self) -> Option<u16> {
+        self.borrow_mut().take_fault()
+    }
+}
+
+// A flat 64 KiB address space of plain RAM
+#[derive(Debug, PartialEq, Eq)]
+pub struct Memory {
+    data: Vec<u8>,
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self {
+            data: vec![0; 0x10000],
+        }
+    }
+}
+
+impl Bus for Memory {
+    fn read(&mut self, addr: u16) -> u8 {
+        self.data[addr as usize]
+    }
+
+    fn write(&mut self, addr: u16, value: u8) {
+        self.data[addr as usize] = value;
+    }
+}
+
+impl Index<usize> for Memory {
+    type Output = u8;
+
+    fn index(&self, index: usize) -> &u8 {
+        &self.data[index]
+    }
+}
+
+impl IndexMut<usize> for Memory {
+    fn index_mut(&mut self, index: usize) -> &mut u8 {
+        &mut self.data[index]
+    }
+}
+
+// Nothing at all on the bus, for a CPU driven through its pins where the
+// host answers every access itself. Reads float high
+#[derive(Debug, Default)]
+pub struct Unconnected;
+
+impl Bus for Unconnected {
+    fn read(&mut self, _addr: u16) -> u8 {
+        0xFF
+    }
+
+    fn write(&mut self, _addr: u16, _value: u8) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_and_write() {
+        let mut memory = Memory::new();
+
+        memory.write(0x0000, 0x42);
+        memory.write(0xFFFF, 0x69);
+        assert_eq!(memory.read(0x0000), 0x42);
+        assert_eq!(memory.read(0xFFFF), 0x69);
+        assert_eq!(memory[0xFFFF], 0x69);
+    }
+}
@@ -0,0 +1,316 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::bus::Bus;
+
+// A bus assembled from regions declared over the address space. Regions
+// declared later take priority over earlier ones where they overlap, and
+// any address not covered by a region is unmapped. Unlike a region
+// declared unmapped, an access there is also reported as a fault
+pub struct MemoryMap {
+    regions: Vec<Region>,
+
+    // The last value seen on the data bus, which is what the CPU reads
+    // back from addresses that nothing drives
+    open_bus: u8,
+
+    fault: Option<u16>,
+}
+
+struct Region {
+    start: u16,
+    end: u16,
+    kind: RegionKind,
+}
+
+enum RegionKind {
+    Ram(Vec<u8>),
+    Rom(Vec<u8>),
+    Unmapped,
+    Device(Box<dyn Bus>),
+}
+
+impl MemoryMap {
+    pub fn builder() -> MemoryMapBuilder {
+        MemoryMapBuilder::default()
+    }
+
+    fn region(&mut self, addr: u16) -> Option<&mut Region> {
+        self.regions
+            .iter_mut()
+            .rev()
+            .find(|region| (region.start..=region.end).contains(&addr))
+    }
+}
+
+impl Bus for MemoryMap {
+    fn read(&mut self, addr: u16) -> u8 {
+        let value = match self.region(addr) {
+            Some(region) => {
+                let offset = addr - region.start;
+
+                match &mut region.kind {
+                    RegionKind::Ram(data) | RegionKind::Rom(data) => {
+                        Some(data[offset as usize % data.len()])
+                    }
+                    RegionKind::Device(device) => Some(device.read(offset)),
+                    RegionKind::Unmapped => None,
+                }
+            }
+            None => {
+                self.fault = Some(addr);
+                None
+            }
+        };
+
+        if let Some(value) = value {
+            self.open_bus = value;
+        }
+
+        self.open_bus
+    }
+
+    fn write(&mut self, addr: u16, value: u8) {
+        self.open_bus = value;
+
+        let Some(region) = self.region(addr) else {
+            self.fault = Some(addr);
+            return;
+        };
+
+        let offset = addr - region.start;
+        match &mut region.kind {
+            RegionKind::Ram(data) => {
+                let len = data.len();
+                data[offset as usize % len] = value;
+            }
+            RegionKind::Device(device) => device.write(offset, value),
+            RegionKind::Rom(_) | RegionKind::Unmapped => {}
+        }
+    }
+
+    // Faults reported by devices are moved into the address space
+    fn take_fault(&mut self) -> Option<u16> {
+        let mut fault = self.fault.take();
+
+        for region in &mut self.regions {
+            if let RegionKind::Device(device) = &mut region.kind {
+                if let Some(offset) = device.take_fault() {
+                    fault = fault.or(Some(region.start.wrapping_add(offset)));
+                }
+            }
+        }
+
+        fault
+    }
+}
+
+impl fmt::Debug for MemoryMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+
+        for region in &self.regions {
+            let kind = match &region.kind {
+                RegionKind::Ram(data) => format!("RAM ({} bytes)", data.len()),
+                RegionKind::Rom(data) => format!("ROM ({} bytes)", data.len()),
+                RegionKind::Unmapped => "unmapped".to_string(),
+                RegionKind::Device(_) => "device".to_string(),
+            };
+            list.entry(&format_args!(
+                "${:04X}-${:04X} {}",
+                region.start, region.end, kind
+            ));
+        }
+
+        list.finish()
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryMapBuilder {
+    regions: Vec<Region>,
+}
+
+impl MemoryMapBuilder {
+    // RAM of the given size, mirrored across the whole range when the
+    // range is larger than the RAM
+    pub fn ram(self, range: RangeInclusive<u16>, size: usize) -> Self {
+        assert!(size > 0, "RAM must be at least one byte");
+        self.region(range, RegionKind::Ram(vec![0; size]))
+    }
+
+    // ROM holding a copy of the given image, mirrored across the range
+    // like RAM. Writes are ignored
+    pub fn rom(self, range: RangeInclusive<u16>, image: &[u8]) -> Self {
+        assert!(!image.is_empty(), "ROM image must not be empty");
+        self.region(range, RegionKind::Rom(image.to_vec()))
+    }
+
+    // Nothing responds here, so reads return the open bus value
+    pub fn unmapped(self, range: RangeInclusive<u16>) -> Self {
+        self.region(range, RegionKind::Unmapped)
+    }
+
+    // Hands every access in the range to the device, with the address
+    // given as an offset from the start of the range
+    pub fn device(self, range: RangeInclusive<u16>, device: impl Bus + 'static) -> Self {
+        self.region(range, RegionKind::Device(Box::new(device)))
+    }
+
+    pub fn build(self) -> MemoryMap {
+        MemoryMap {
+            regions: self.regions,
+            open_bus: 0,
+            fault: None,
+        }
+    }
+
+    fn region(mut self, range: RangeInclusive<u16>, kind: RegionKind) -> Self {
+        assert!(!range.is_empty(), "region must cover at least one address");
+        self.regions.push(Region {
+            start: *range.start(),
+            end: *range.end(),
+            kind,
+        });
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cpu::Cpu6502;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // Latches the last write to each of eight registers, mirrored
+    // across the range
+    #[derive(Default)]
+    struct Registers {
+        values: [u8; 8],
+        writes: Vec<(u16, u8)>,
+    }
+
+    impl Bus for Registers {
+        fn read(&mut self, addr: u16) -> u8 {
+            self.values[(addr & 0x07) as usize]
+        }
+
+        fn write(&mut self, addr: u16, value: u8) {
+            self.writes.push((addr, value));
+            self.values[(addr & 0x07) as usize] = value;
+        }
+    }
+
+    #[test]
+    fn ram_is_mirrored() {
+        let mut map = MemoryMap::builder().ram(0x0000..=0x1FFF, 0x0800).build();
+
+        map.write(0x0042, 0x69);
+        assert_eq!(map.read(0x0042), 0x69);
+        assert_eq!(map.read(0x0842), 0x69);
+        assert_eq!(map.read(0x1042), 0x69);
+        assert_eq!(map.read(0x1842), 0x69);
+
+        map.write(0x1FFF, 0x42);
+        assert_eq!(map.read(0x07FF), 0x42);
+    }
+
+    #[test]
+    fn rom_ignores_writes() {
+        let mut map = MemoryMap::builder()
+            .rom(0x8000..=0xFFFF, &[0xDE, 0xAD, 0xBE, 0xEF])
+            .build();
+
+        map.write(0x8000, 0x00);
+        assert_eq!(map.read(0x8000), 0xDE);
+        assert_eq!(map.read(0x8003), 0xEF);
+        assert_eq!(map.read(0xFFFF), 0xEF);
+    }
+
+    #[test]
+    fn unmapped_reads_open_bus() {
+        let mut map = MemoryMap::builder()
+            .ram(0x0000..=0x07FF, 0x0800)
+            .unmapped(0x0400..=0x04FF)
+            .build();
+
+        map.write(0x0042, 0x69);
+        map.read(0x0042);
+        assert_eq!(map.read(0x4000), 0x69);
+        assert_eq!(map.read(0x0450), 0x69);
+
+        // a write to an unmapped address still drives the bus
+        map.write(0x0450, 0x42);
+        assert_eq!(map.read(0x0450), 0x42);
+        assert_eq!(map.read(0x0050), 0x00);
+        assert_eq!(map.read(0x4000), 0x00);
+    }
+
+    #[test]
+    fn uncovered_addresses_fault() {
+        let mut map = MemoryMap::builder()
+            .ram(0x0000..=0x07FF, 0x0800)
+            .unmapped(0x0800..=0x0FFF)
+            .build();
+
+        map.read(0x0900);
+        map.write(0x0042, 0x69);
+        assert_eq!(map.take_fault(), None);
+
+        map.write(0x4000, 0x69);
+        assert_eq!(map.take_fault(), Some(0x4000));
+        assert_eq!(map.take_fault(), None);
+
+        map.read(0x1000);
+        assert_eq!(map.take_fault(), Some(0x1000));
+    }
+
+    #[test]
+    fn devices_see_offsets() {
+        let registers = Rc::new(RefCell::new(Registers::default()));
+        let mut map = MemoryMap::builder()
+            .ram(0x0000..=0x1FFF, 0x0800)
+            .device(0x2000..=0x3FFF, registers.clone())
+            .build();
+
+        map.write(0x2001, 0x69);
+        map.write(0x3FFA, 0x42);
+        assert_eq!(map.read(0x2009), 0x69);
+        assert_eq!(map.read(0x2002), 0x42);
+        assert_eq!(
+            registers.borrow().writes,
+            vec![(0x0001, 0x69), (0x1FFA, 0x42)]
+        );
+    }
+
+    #[test]
+    fn run_from_memory_map() {
+        let mut rom = vec![0xEA; 0x4000];
+        rom[0x0000] = 0xA9; // LDA #$69
+        rom[0x0001] = 0x69;
+        rom[0x0002] = 0x8D; // STA $2000
+        rom[0x0003] = 0x00;
+        rom[0x0004] = 0x20;
+        rom[0x0005] = 0x85; // STA $42
+        rom[0x0006] = 0x42;
+        rom[0x0007] = 0xFF;
+        rom[0x3FFC] = 0x00;
+        rom[0x3FFD] = 0xC0;
+
+        let registers = Rc::new(RefCell::new(Registers::default()));
+        let map = MemoryMap::builder()
+            .ram(0x0000..=0x1FFF, 0x0800)
+            .device(0x2000..=0x2007, registers.clone())
+            .rom(0x8000..=0xFFFF, &rom)
+            .build();
+
+        let mut cpu = Cpu6502::with_bus(map);
+        cpu.reset();
+        assert_eq!(cpu.ip, 0xC000);
+
+        cpu.run().unwrap();
+        assert_eq!(registers.borrow().values[0], 0x69);
+        assert_eq!(cpu.bus.read(0x0842), 0x69);
+    }
+}
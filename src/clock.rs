@@ -0,0 +1,151 @@
+use std::time::{Duration, Instant};
+
+// Clock frequencies in hertz of some machines built around the 6502
+pub const ONE_MHZ: f64 = 1_000_000.0;
+pub const NES_NTSC: f64 = 1_789_773.0;
+pub const APPLE_II: f64 = 1_022_727.0;
+pub const C64_PAL: f64 = 985_248.0;
+
+// Paces emulation to a real clock frequency by cycle count. Progress is
+// measured from an anchor point rather than from the last check, so time
+// lost oversleeping one wait is caught up over the next ones instead of
+// adding up to drift. Falling further behind than the maximum lag, say
+// when the host stalls, moves the anchor up rather than racing to catch up
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    frequency: f64,
+    speed: f64,
+    max_lag: Duration,
+
+    // The time and total cycle count pacing is measured from
+    anchor: Option<(Instant, u64)>,
+}
+
+impl Throttle {
+    pub fn new(frequency: f64) -> Self {
+        assert!(frequency > 0.0, "clock frequency must be positive");
+
+        Self {
+            frequency,
+            speed: 1.0,
+            max_lag: Duration::from_millis(100),
+            anchor: None,
+        }
+    }
+
+    // Runs the clock at a multiple of its frequency, such as 4.0 to fast
+    // forward or 0.5 for slow motion
+    pub fn speed(mut self, multiplier: f64) -> Self {
+        self.set_speed(multiplier);
+        self
+    }
+
+    pub fn set_speed(&mut self, multiplier: f64) {
+        assert!(multiplier > 0.0, "speed multiplier must be positive");
+        self.speed = multiplier;
+        self.anchor = None;
+    }
+
+    // How far emulation may fall behind before giving up on catching up
+    pub fn max_lag(mut self, lag: Duration) -> Self {
+        self.max_lag = lag;
+        self
+    }
+
+    // How far ahead of real time a total of `cycles` puts emulation at
+    // `now`, which is how long to wait before carrying on
+    pub fn ahead(&mut self, cycles: u64, now: Instant) -> Duration {
+        let (start, base) = *self.anchor.get_or_insert((now, cycles));
+
+        let rate = self.frequency * self.speed;
+        let emulated = Duration::from_secs_f64(cycles.saturating_sub(base) as f64 / rate);
+        let elapsed = now.saturating_duration_since(start);
+
+        if elapsed > emulated + self.max_lag {
+            self.anchor = Some((now, cycles));
+            return Duration::ZERO;
+        }
+
+        emulated.saturating_sub(elapsed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cpu::Cpu6502;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn waits_for_the_clock() {
+        let start = Instant::now();
+        let mut throttle = Throttle::new(ONE_MHZ);
+        assert_eq!(throttle.ahead(0, start), Duration::ZERO);
+
+        assert_eq!(throttle.ahead(1_000, start), MS);
+        assert_eq!(throttle.ahead(1_000, start + MS / 2), MS / 2);
+        assert_eq!(throttle.ahead(10_000, start + MS), 9 * MS);
+    }
+
+    #[test]
+    fn speed_multiplier() {
+        let start = Instant::now();
+        let mut throttle = Throttle::new(ONE_MHZ).speed(2.0);
+        throttle.ahead(0, start);
+        assert_eq!(throttle.ahead(1_000, start), MS / 2);
+
+        // changing speed starts pacing again from the next check
+        throttle.set_speed(0.5);
+        throttle.ahead(1_000, start + MS);
+        assert_eq!(throttle.ahead(2_000, start + MS), 2 * MS);
+    }
+
+    #[test]
+    fn catches_up_small_delays() {
+        let start = Instant::now();
+        let mut throttle = Throttle::new(ONE_MHZ);
+        throttle.ahead(0, start);
+
+        // running late doesn't move the goalposts, so the time lost is
+        // made up by not waiting until emulation is ahead again
+        assert_eq!(throttle.ahead(1_000, start + 3 * MS), Duration::ZERO);
+        assert_eq!(throttle.ahead(3_000, start + 3 * MS), Duration::ZERO);
+        assert_eq!(throttle.ahead(5_000, start + 3 * MS), 2 * MS);
+    }
+
+    #[test]
+    fn gives_up_on_long_stalls() {
+        let start = Instant::now();
+        let mut throttle = Throttle::new(ONE_MHZ).max_lag(10 * MS);
+        throttle.ahead(0, start);
+
+        assert_eq!(throttle.ahead(1_000, start + 50 * MS), Duration::ZERO);
+        assert_eq!(throttle.ahead(2_000, start + 50 * MS), MS);
+    }
+
+    #[test]
+    fn run_throttled() {
+        // counting down 2000 times at five cycles a go takes around 10ms
+        // at 1MHz
+        let program = vec![
+            0xA2, 0xD0, // LDX #$D0
+            0xA0, 0x08, // LDY #$08
+            0xCA, // DEX
+            0xD0, 0xFD, // BNE -3
+            0x88, // DEY
+            0xD0, 0xFA, // BNE -6
+            0xFF,
+        ];
+        let mut cpu = Cpu6502::with_program(program);
+        let mut throttle = Throttle::new(ONE_MHZ);
+
+        let start = Instant::now();
+        cpu.run_throttled(&mut throttle).unwrap();
+        let elapsed = start.elapsed();
+
+        let expected = Duration::from_secs_f64(cpu.cycles as f64 / ONE_MHZ);
+        assert!(cpu.cycles > 9_000);
+        assert!(elapsed >= expected - MS, "{elapsed:?} < {expected:?}");
+    }
+}
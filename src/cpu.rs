@@ -1,24 +1,108 @@
+mod addressing;
+mod error;
+mod execute;
 mod flags;
+mod instruction;
+mod microcode;
 mod opcodes;
+mod pins;
+mod run;
+mod step;
+mod trap;
 
-use flags::Flags;
-use opcodes::*;
+use std::collections::VecDeque;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::bus::{Bus, Memory};
+use crate::clock::Throttle;
+use crate::image::Image;
+use crate::tasks::Task;
+use addressing::Resolved;
+
+pub use error::{CpuError, ErrorPolicy};
+pub use flags::Flags;
+pub use instruction::{disassemble, lookup, AddressingMode, Instruction, Mnemonic, OPCODES};
+pub use opcodes::UnstableModel;
+pub use pins::Pins;
+pub use run::StopReason;
+pub use step::{Registers, Step};
+pub use trap::{Trap, TrapPolicy, JAM_OPCODES};
+
+pub const NMI_VECTOR: u16 = 0xFFFA;
+pub const RESET_VECTOR: u16 = 0xFFFC;
+pub const IRQ_VECTOR: u16 = 0xFFFE;
 
 #[derive(Debug)]
-pub struct Cpu6502 {
+pub struct Cpu6502<B: Bus = Memory> {
     pub a: u8,
     pub x: u8,
     pub y: u8,
     pub flags: Flags,
     pub ip: u16,
-    pub sp: u16,
-    pub memory: Vec<u8>,
+    pub sp: u8,
+    pub bus: B,
     pub halted: bool,
 
+    // Set by a JAM opcode, which locks the CPU up until reset
+    pub jammed: bool,
+
+    // What halts the CPU, what halted it last, and the value the current
+    // instruction last wrote to the exit port
+    pub traps: TrapPolicy,
+    pub trap: Option<Trap>,
+    exit_code: Option<u8>,
+
+    // Whether unimplemented opcodes and bus faults stop the CPU with an
+    // error, and the error waiting to be returned
+    pub errors: ErrorPolicy,
+    error: Option<CpuError>,
+
+    // How the undocumented opcodes that vary between chips behave
+    pub unstable: UnstableModel,
+
+    // Total number of clock cycles run since the CPU was created
+    pub cycles: u64,
+
+    // Cycles taken by the last instruction on top of its base count,
+    // such as for a taken branch or an indexed read crossing a page
+    pub extra_cycles: u8,
+
+    // State of the interrupt input lines. IRQ is level-triggered, while
+    // NMI latches a pending interrupt on each rising edge
+    irq_line: bool,
+    nmi_line: bool,
+    nmi_pending: bool,
+
     // This is a pointer into the 6502's memory space
     // It is for managing interal state of the emulator
     // and is not part of the 6502
     pointer: u16,
+
+    // The last instruction run whole and where it found its operand, for
+    // step
+    executed: Option<Instruction>,
+    resolved: Option<Resolved>,
+
+    // State of the cycle-stepped engine driven by tick: the cycles left in
+    // the current instruction, its address, the instruction itself, the
+    // byte latched between cycles and whether indexing carried into the
+    // high byte
+    tasks: VecDeque<Task>,
+    opcode_address: Option<u16>,
+    mnemonic: Mnemonic,
+    data: u8,
+    page_crossed: bool,
+
+    // The cycle whose address is out on the pins, with whether SYNC marks
+    // it as an opcode fetch, and whether RES has been held down
+    pin_cycle: Option<(Task, bool)>,
+    res_line: bool,
+
+    // The bytes fetched at the instruction pointer by the current
+    // instruction, so step can report them without reading them again
+    fetched: [u8; 3],
+    fetched_len: usize,
 }
 
 impl Cpu6502 {
@@ -31,37 +115,157 @@ impl Cpu6502 {
         cpu.load_program(program);
         cpu
     }
+}
+
+impl<B: Bus> Cpu6502<B> {
+    pub fn with_bus(bus: B) -> Self {
+        Self {
+            a: 0,
+            x: 0,
+            y: 0,
+            ip: 0,
+            sp: 0,
+            flags: Flags::default(),
+            bus,
+            pointer: 0,
+            executed: None,
+            resolved: None,
+            halted: false,
+            jammed: false,
+            traps: TrapPolicy::default(),
+            trap: None,
+            exit_code: None,
+            errors: ErrorPolicy::default(),
+            error: None,
+            unstable: UnstableModel::default(),
+            cycles: 0,
+            extra_cycles: 0,
+            irq_line: false,
+            nmi_line: false,
+            nmi_pending: false,
+            tasks: VecDeque::new(),
+            opcode_address: None,
+            mnemonic: Mnemonic::Nop,
+            data: 0,
+            page_crossed: false,
+            pin_cycle: None,
+            res_line: false,
+            fetched: [0; 3],
+            fetched_len: 0,
+        }
+    }
 
+    // Loads the program at $8000 and points the reset vector at it
     pub fn load_program(&mut self, program: Vec<u8>) {
-        // splice() will remove n elements, even if n is larger than the size
-        // of the iterator being spliced in, so we must calculate the start
-        // and end of the range based on program size
-        let (start, end): (usize, usize) = (0x8000, 0x8000 + program.len());
-        self.memory.splice(start..end, program);
+        let image = Image::new().segment(0x8000, program).reset_vector(0x8000);
+        self.load_image(&image);
+    }
 
-        // reset vector
-        self.memory[0xFFFC] = 0x00;
-        self.memory[0xFFFD] = 0x80;
+    // Copies each segment of the image into memory through the bus, then
+    // resets. Execution starts at the image's entry point if it has one,
+    // otherwise it follows the reset vector
+    pub fn load_image(&mut self, image: &Image) {
+        for segment in &image.segments {
+            self.load_at(segment.origin, &segment.data);
+        }
 
         self.reset();
+
+        if let Some(entry) = image.entry {
+            self.ip = entry;
+        }
+    }
+
+    // Copies the data into memory starting at origin, without touching
+    // the vectors or the registers
+    pub fn load_at(&mut self, origin: u16, data: &[u8]) {
+        for (offset, &byte) in data.iter().enumerate() {
+            self.bus.write(origin.wrapping_add(offset as u16), byte);
+        }
     }
 
     pub fn reset(&mut self) {
-        self.set_pointer_low(self.memory[0xFFFC]);
-        self.set_pointer_high(self.memory[0xFFFD]);
-        self.ip = self.pointer;
+        self.ip = self.read_vector(RESET_VECTOR);
+        self.sp = 0xFD;
         self.halted = false;
+        self.jammed = false;
+        self.trap = None;
+        self.exit_code = None;
+        self.error = None;
+        self.nmi_pending = false;
+        self.tasks.clear();
+        self.opcode_address = None;
+        self.pin_cycle = None;
+    }
+
+    pub fn irq(&mut self, asserted: bool) {
+        self.irq_line = asserted;
+    }
+
+    pub fn nmi(&mut self, asserted: bool) {
+        if asserted && !self.nmi_line {
+            self.nmi_pending = true;
+        }
+
+        self.nmi_line = asserted;
     }
 
-    pub fn run(&mut self) {
-        loop {
-            if self.halted {
-                break;
+    // Runs a whole instruction at a time until halted, jammed or stopped by
+    // an error. An instruction left part way through by tick is finished
+    // first
+    pub fn run(&mut self) -> Result<(), CpuError> {
+        self.finish_ticked_instruction()?;
+
+        while !self.halted && !self.jammed {
+            self.run_instruction();
+            self.take_error()?;
+        }
+
+        Ok(())
+    }
+
+    // Runs like run, but paced to the throttle's clock. Waits shorter than
+    // a millisecond are left to build up, as the host can't sleep for less
+    pub fn run_throttled(&mut self, throttle: &mut Throttle) -> Result<(), CpuError> {
+        const GRANULARITY: Duration = Duration::from_millis(1);
+
+        self.finish_ticked_instruction()?;
+
+        while !self.halted && !self.jammed {
+            self.run_instruction();
+            self.take_error()?;
+
+            let ahead = throttle.ahead(self.cycles, Instant::now());
+            if ahead >= GRANULARITY {
+                thread::sleep(ahead);
             }
+        }
 
-            let opcode = self.fetch_byte();
-            self.decode(opcode);
+        Ok(())
+    }
+
+    fn finish_ticked_instruction(&mut self) -> Result<(), CpuError> {
+        while !self.tasks.is_empty() && !self.halted {
+            self.tick();
         }
+
+        self.take_error()
+    }
+
+    // Runs the next instruction, after any interrupt that's waiting, and
+    // returns its address
+    fn run_instruction(&mut self) -> u16 {
+        self.poll_interrupts();
+
+        let address = self.ip;
+        self.fetched_len = 0;
+        let opcode = self.fetch_byte();
+        self.decode(opcode);
+        self.trap_exit();
+        self.check_jump_to_self(address);
+        self.check_bus_fault();
+
+        address
     }
 
     fn set_pointer_high(&mut self, value: u8) {
@@ -74,196 +278,115 @@ impl Cpu6502 {
 
     fn fetch_byte(&mut self) -> u8 {
         let addr = self.ip;
-        self.ip += 1;
-        self.memory[addr as usize]
+        self.ip = self.ip.wrapping_add(1);
+        let byte = self.bus.read(addr);
+
+        if let Some(slot) = self.fetched.get_mut(self.fetched_len) {
+            *slot = byte;
+            self.fetched_len += 1;
+        }
+
+        byte
+    }
+
+    fn read_memory(&mut self) -> u8 {
+        self.bus.read(self.pointer)
+    }
+
+    fn write_memory(&mut self, value: u8) {
+        self.bus.write(self.pointer, value);
+        self.check_exit_port(self.pointer, value);
+    }
+
+    fn read_vector(&mut self, vector: u16) -> u16 {
+        self.pointer = vector;
+        let low = self.read_memory();
+        self.pointer = vector.wrapping_add(1);
+        let high = self.read_memory();
+        self.set_pointer_high(high);
+        self.set_pointer_low(low);
+        self.pointer
+    }
+
+    // Read-modify-write instructions write the unmodified value back before
+    // writing the result, just like the real bus traffic
+    fn read_modify_write(&mut self, op: fn(&mut Self, u8) -> u8) {
+        let value = self.read_memory();
+        self.write_memory(value);
+        let result = op(self, value);
+        self.write_memory(result);
+    }
+
+    // The stack lives on page one and the stack pointer wraps within it
+    fn push(&mut self, value: u8) {
+        self.set_pointer_high(0x01);
+        self.set_pointer_low(self.sp);
+        self.write_memory(value);
+        self.sp = self.sp.wrapping_sub(1);
+    }
+
+    fn pull(&mut self) -> u8 {
+        self.sp = self.sp.wrapping_add(1);
+        self.set_pointer_high(0x01);
+        self.set_pointer_low(self.sp);
+        self.read_memory()
     }
 
-    fn read_memory(&self) -> u8 {
-        self.memory[self.pointer as usize]
+    // Interrupts are only recognised between instructions, and NMI wins
+    // over IRQ when both are waiting
+    fn poll_interrupts(&mut self) {
+        if self.jammed {
+            return;
+        }
+
+        if self.nmi_pending {
+            self.nmi_pending = false;
+            self.interrupt(NMI_VECTOR, false);
+            self.cycles += 7;
+        } else if self.irq_line && !self.flags.interrupt_disable {
+            self.interrupt(IRQ_VECTOR, false);
+            self.cycles += 7;
+        }
     }
 
-    fn _write_memory(&mut self, value: u8) {
-        self.memory[self.pointer as usize] = value;
+    // Pushes the return address and status and jumps through the vector
+    fn interrupt(&mut self, vector: u16, brk: bool) {
+        let [ret_low, ret_high] = self.ip.to_le_bytes();
+        self.push(ret_high);
+        self.push(ret_low);
+
+        self.push(self.flags.pushed(brk));
+        self.flags.set_interrupt_disable();
+        self.ip = self.read_vector(vector);
     }
 
     fn decode(&mut self, opcode: u8) {
-        match opcode {
-            0x00 => {}
-            0x01 => {}
-            0x05 => {}
-            0x06 => {}
-            0x08 => {}
-            0x09 => {}
-            0x0A => {}
-            0x0D => {}
-            0x0E => {}
-            0x10 => {}
-            0x11 => {}
-            0x12 => {}
-            0x15 => {}
-            0x16 => {}
-            0x18 => {}
-            0x19 => {}
-            0x1D => {}
-            0x1E => {}
-            0x20 => {}
-            0x21 => {}
-            0x24 => {}
-            0x25 => {}
-            0x26 => {}
-            0x28 => {}
-            0x29 => {}
-            0x2A => {}
-            0x2C => {}
-            0x2D => {}
-            0x2E => {}
-            0x30 => {}
-            0x31 => {}
-            0x35 => {}
-            0x36 => {}
-            0x38 => {}
-            0x39 => {}
-            0x3D => {}
-            0x3E => {}
-            0x40 => {}
-            0x41 => {}
-            0x45 => {}
-            0x46 => {}
-            0x48 => {}
-            0x49 => {}
-            0x4A => {}
-            0x4C => {}
-            0x4D => {}
-            0x4E => {}
-            0x50 => {}
-            0x51 => {}
-            0x55 => {}
-            0x56 => {}
-            0x58 => {}
-            0x59 => {}
-            0x5D => {}
-            0x5E => {}
-            0x60 => {}
-            0x61 => {}
-            0x65 => {}
-            0x66 => {}
-            0x68 => {}
-            0x69 => {}
-            0x6A => {}
-            0x6C => {}
-            0x6D => {}
-            0x6E => {}
-            0x70 => {}
-            0x71 => {}
-            0x75 => {}
-            0x76 => {}
-            0x78 => {}
-            0x79 => {}
-            0x7D => {}
-            0x7E => {}
-            0x81 => {}
-            0x84 => {}
-            0x85 => {}
-            0x86 => {}
-            0x88 => {}
-            0x8A => {}
-            0x8C => {}
-            0x8D => {}
-            0x8E => {}
-            0x90 => {}
-            0x91 => {}
-            0x94 => {}
-            0x95 => {}
-            0x96 => {}
-            0x98 => {}
-            0x99 => {}
-            0x9A => {}
-            0x9D => {}
-            0xA0 => load_y_immediate(self),
-            0xA1 => load_a_indirect_x(self),
-            0xA2 => load_x_immediate(self),
-            0xA4 => load_y_zeropage(self),
-            0xA5 => load_a_zeropage(self),
-            0xA6 => load_x_zeropage(self),
-            0xA8 => {}
-            0xA9 => load_a_immediate(self),
-            0xAA => {}
-            0xAC => load_y_absolute(self),
-            0xAD => load_a_absolute(self),
-            0xAE => load_x_absolute(self),
-            0xB0 => {}
-            0xB1 => load_a_indirect_y(self),
-            0xB4 => load_y_zeropage_x(self),
-            0xB5 => load_a_zeropage_x(self),
-            0xB6 => load_x_zeropage_y(self),
-            0xB8 => {}
-            0xB9 => load_a_absolute_y(self),
-            0xBA => {}
-            0xBC => load_y_absolute_x(self),
-            0xBD => load_a_absolute_x(self),
-            0xBE => load_x_absolute_y(self),
-            0xC0 => {}
-            0xC1 => {}
-            0xC4 => {}
-            0xC5 => {}
-            0xC6 => {}
-            0xC8 => {}
-            0xC9 => {}
-            0xCA => {}
-            0xCC => {}
-            0xCD => {}
-            0xCE => {}
-            0xD0 => {}
-            0xD1 => {}
-            0xD5 => {}
-            0xD6 => {}
-            0xD8 => {}
-            0xD9 => {}
-            0xDD => {}
-            0xDE => {}
-            0xE0 => {}
-            0xE1 => {}
-            0xE4 => {}
-            0xE5 => {}
-            0xE6 => {}
-            0xE8 => {}
-            0xE9 => {}
-            0xEA => {}
-            0xEC => {}
-            0xED => {}
-            0xEE => {}
-            0xF0 => {}
-            0xF1 => {}
-            0xF5 => {}
-            0xF6 => {}
-            0xF8 => {}
-            0xF9 => {}
-            0xFA => {}
-            0xFD => {}
-            0xFE => {}
-            0xFF => self.halted = true,
-            _ => {}
+        self.extra_cycles = 0;
+        self.executed = None;
+        self.resolved = None;
+
+        if self.trap_opcode(opcode) {
+            return;
+        }
+
+        let instruction = self.instruction(opcode);
+        if self.refuse_opcode(opcode, self.ip.wrapping_sub(1), instruction) {
+            return;
         }
+
+        let Some(instruction) = instruction else {
+            return;
+        };
+
+        self.executed = Some(instruction);
+        self.execute(instruction);
+        self.cycles += (instruction.cycles + self.extra_cycles) as u64;
     }
 }
 
-impl Default for Cpu6502 {
+impl<B: Bus + Default> Default for Cpu6502<B> {
     fn default() -> Self {
-        // We must fill the memory vec to set the len()
-        // or we'll get index bounds panics in load_program()
-        let memory: Vec<u8> = vec![0; 0xFFFF];
-
-        Self {
-            a: 0,
-            x: 0,
-            y: 0,
-            ip: 0,
-            sp: 0,
-            flags: Flags::default(),
-            memory,
-            pointer: 0,
-            halted: false,
-        }
+        Self::with_bus(B::default())
     }
 }
 
@@ -273,15 +396,22 @@ mod test {
 
     const PROG: [u8; 4] = [0xDE, 0xAD, 0xBE, 0xEF];
 
+    impl<B: Bus> Cpu6502<B> {
+        fn run_one(&mut self) {
+            let opcode = self.fetch_byte();
+            self.decode(opcode);
+        }
+    }
+
     #[test]
     fn load_program_into_memory() {
         let mut cpu = Cpu6502::new();
         cpu.load_program(PROG.to_vec());
 
-        assert_eq!(cpu.memory[0x8000], 0xDE);
-        assert_eq!(cpu.memory[0x8001], 0xAD);
-        assert_eq!(cpu.memory[0x8002], 0xBE);
-        assert_eq!(cpu.memory[0x8003], 0xEF);
+        assert_eq!(cpu.bus[0x8000], 0xDE);
+        assert_eq!(cpu.bus[0x8001], 0xAD);
+        assert_eq!(cpu.bus[0x8002], 0xBE);
+        assert_eq!(cpu.bus[0x8003], 0xEF);
     }
 
     #[test]
@@ -289,7 +419,215 @@ mod test {
         let mut cpu = Cpu6502::new();
         cpu.load_program(PROG.to_vec());
 
-        assert_eq!(cpu.memory[0xFFFC], 0x00);
-        assert_eq!(cpu.memory[0xFFFD], 0x80);
+        assert_eq!(cpu.bus[0xFFFC], 0x00);
+        assert_eq!(cpu.bus[0xFFFD], 0x80);
+    }
+
+    // Wraps plain memory and records every access made through the bus
+    #[derive(Default)]
+    struct RecordingBus {
+        memory: Memory,
+        reads: Vec<u16>,
+        writes: Vec<(u16, u8)>,
+    }
+
+    impl Bus for RecordingBus {
+        fn read(&mut self, addr: u16) -> u8 {
+            self.reads.push(addr);
+            self.memory.read(addr)
+        }
+
+        fn write(&mut self, addr: u16, value: u8) {
+            self.writes.push((addr, value));
+            self.memory.write(addr, value);
+        }
+    }
+
+    #[test]
+    fn accesses_go_through_bus() {
+        let mut cpu: Cpu6502<RecordingBus> = Cpu6502::default();
+        cpu.load_program(vec![0xAD, 0x69, 0x42, 0x8D, 0x00, 0x02, 0xFF]);
+        cpu.bus.memory[0x4269] = 0x42;
+        cpu.bus.reads.clear();
+        cpu.bus.writes.clear();
+
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x42);
+        assert_eq!(
+            cpu.bus.reads,
+            vec![0x8000, 0x8001, 0x8002, 0x4269, 0x8003, 0x8004, 0x8005, 0x8006]
+        );
+        assert_eq!(cpu.bus.writes, vec![(0x0200, 0x42)]);
+    }
+
+    #[test]
+    fn read_modify_write_writes_twice() {
+        let mut cpu: Cpu6502<RecordingBus> = Cpu6502::default();
+        cpu.load_program(vec![0x0E, 0x69, 0x42, 0xEE, 0x69, 0x42, 0xFF]);
+        cpu.bus.memory[0x4269] = 0x21;
+        cpu.bus.writes.clear();
+
+        cpu.run().unwrap();
+        assert_eq!(
+            cpu.bus.writes,
+            vec![
+                (0x4269, 0x21),
+                (0x4269, 0x42),
+                (0x4269, 0x42),
+                (0x4269, 0x43)
+            ]
+        );
+    }
+
+    #[test]
+    fn load_at_leaves_vectors_alone() {
+        let mut cpu = Cpu6502::new();
+        cpu.load_program(PROG.to_vec());
+        cpu.load_at(0x0200, &PROG);
+
+        assert_eq!(cpu.bus[0x0200], 0xDE);
+        assert_eq!(cpu.bus[0x0203], 0xEF);
+        assert_eq!(cpu.bus[0xFFFC], 0x00);
+        assert_eq!(cpu.bus[0xFFFD], 0x80);
+    }
+
+    #[test]
+    fn address_space_is_64k() {
+        let mut cpu = Cpu6502::new();
+        cpu.load_at(0xFFFF, &[0x69]);
+        assert_eq!(cpu.bus.read(0xFFFF), 0x69);
+
+        // the instruction pointer wraps from $FFFF to $0000
+        cpu.bus[0xFFFF] = 0xA9;
+        cpu.bus[0x0000] = 0x69;
+        cpu.bus[0x0001] = 0xFF;
+        cpu.ip = 0xFFFF;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.ip, 0x0002);
+    }
+
+    #[test]
+    fn stack_pointer_on_reset() {
+        let mut cpu = Cpu6502::new();
+        cpu.load_program(PROG.to_vec());
+
+        assert_eq!(cpu.sp, 0xFD);
+    }
+
+    // Handler at $8010 that loads a marker and halts
+    fn interrupt_program() -> Vec<u8> {
+        let mut program = vec![0xEA; 0x14];
+        program[0x00] = 0xA2; // LDX #$42
+        program[0x01] = 0x42;
+        program[0x02] = 0xFF;
+        program[0x10] = 0xA9; // LDA #$69
+        program[0x11] = 0x69;
+        program[0x12] = 0xFF;
+        program
+    }
+
+    #[test]
+    fn irq_jumps_through_vector() {
+        let mut cpu = Cpu6502::with_program(interrupt_program());
+        cpu.bus[0xFFFE] = 0x10;
+        cpu.bus[0xFFFF] = 0x80;
+
+        cpu.irq(true);
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.x, 0x00);
+        assert!(cpu.flags.interrupt_disable);
+
+        // return address, then status with only the unused bit set
+        assert_eq!(cpu.bus[0x01FD], 0x80);
+        assert_eq!(cpu.bus[0x01FC], 0x00);
+        assert_eq!(cpu.bus[0x01FB], 0b0010_0000);
+    }
+
+    #[test]
+    fn irq_is_masked_by_interrupt_disable() {
+        let mut cpu = Cpu6502::with_program(interrupt_program());
+        cpu.bus[0xFFFE] = 0x10;
+        cpu.bus[0xFFFF] = 0x80;
+
+        cpu.flags.interrupt_disable = true;
+        cpu.irq(true);
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x00);
+        assert_eq!(cpu.x, 0x42);
+    }
+
+    #[test]
+    fn irq_is_level_triggered() {
+        let mut program = interrupt_program();
+        program[0x12] = 0x40; // RTI
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0xFFFE] = 0x10;
+        cpu.bus[0xFFFF] = 0x80;
+
+        // while the line stays asserted, RTI clears the mask and the
+        // interrupt is taken again straight away
+        cpu.irq(true);
+        cpu.poll_interrupts();
+        cpu.run_one();
+        cpu.run_one();
+        assert_eq!(cpu.ip, 0x8000);
+        cpu.poll_interrupts();
+        assert_eq!(cpu.ip, 0x8010);
+
+        // once released, execution resumes at the interrupted instruction
+        cpu.irq(false);
+        cpu.run_one();
+        cpu.run_one();
+        cpu.poll_interrupts();
+        assert_eq!(cpu.ip, 0x8000);
+        assert_eq!(cpu.sp, 0xFD);
+    }
+
+    #[test]
+    fn nmi_is_edge_triggered() {
+        let mut cpu = Cpu6502::with_program(interrupt_program());
+        cpu.bus[0xFFFA] = 0x10;
+        cpu.bus[0xFFFB] = 0x80;
+
+        // NMI ignores the interrupt disable flag
+        cpu.flags.interrupt_disable = true;
+        cpu.nmi(true);
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.bus[0x01FB], 0b0010_0100);
+
+        // holding the line doesn't trigger another interrupt
+        cpu.reset();
+        cpu.a = 0x00;
+        cpu.nmi(true);
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x00);
+        assert_eq!(cpu.x, 0x42);
+
+        cpu.reset();
+        cpu.nmi(false);
+        cpu.nmi(true);
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn push_and_pull_wrap_within_page_one() {
+        let mut cpu = Cpu6502::new();
+
+        cpu.sp = 0x00;
+        cpu.push(0x69);
+        assert_eq!(cpu.bus[0x0100], 0x69);
+        assert_eq!(cpu.sp, 0xFF);
+
+        cpu.push(0x42);
+        assert_eq!(cpu.bus[0x01FF], 0x42);
+        assert_eq!(cpu.sp, 0xFE);
+
+        assert_eq!(cpu.pull(), 0x42);
+        assert_eq!(cpu.pull(), 0x69);
+        assert_eq!(cpu.sp, 0x00);
     }
 }
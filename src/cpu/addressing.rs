@@ -0,0 +1,169 @@
+use crate::bus::Bus;
+use crate::cpu::{AddressingMode, Cpu6502};
+
+// Where an addressing mode found the operand, and whether indexing or a
+// branch crossed into another page to get there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Resolved {
+    pub address: u16,
+    pub page_crossed: bool,
+}
+
+impl<B: Bus> Cpu6502<B> {
+    // Fetches the operand bytes for the addressing mode and works out the
+    // effective address, reading any pointer through the bus. Modes with no
+    // address in memory resolve to nothing and fetch nothing
+    pub(super) fn resolve(&mut self, mode: AddressingMode) -> Option<Resolved> {
+        use AddressingMode::*;
+
+        let (address, page_crossed) = match mode {
+            Implied | Accumulator | Immediate => return None,
+            ZeroPage => (self.fetch_byte() as u16, false),
+            ZeroPageX => (self.fetch_byte().wrapping_add(self.x) as u16, false),
+            ZeroPageY => (self.fetch_byte().wrapping_add(self.y) as u16, false),
+            Absolute => (self.fetch_word(), false),
+            AbsoluteX => indexed(self.fetch_word(), self.x),
+            AbsoluteY => indexed(self.fetch_word(), self.y),
+            Indirect => {
+                let pointer = self.fetch_word();
+                (self.read_pointer(pointer), false)
+            }
+            IndirectX => {
+                let pointer = self.fetch_byte().wrapping_add(self.x);
+                (self.read_pointer(pointer as u16), false)
+            }
+            IndirectY => {
+                let pointer = self.fetch_byte();
+                let base = self.read_pointer(pointer as u16);
+                indexed(base, self.y)
+            }
+            Relative => {
+                // The offset is signed and relative to the next instruction
+                let offset = self.fetch_byte() as i8;
+                let target = self.ip.wrapping_add(offset as u16);
+                (target, crosses_page(self.ip, target))
+            }
+        };
+
+        Some(Resolved {
+            address,
+            page_crossed,
+        })
+    }
+
+    fn fetch_word(&mut self) -> u16 {
+        let low = self.fetch_byte();
+        let high = self.fetch_byte();
+        u16::from_le_bytes([low, high])
+    }
+
+    // The 6502 never carries into the high byte of a pointer's address, so
+    // a zero page pointer at $FF wraps to $00 for its high byte, and so
+    // does JMP ($xxFF) within its page
+    fn read_pointer(&mut self, addr: u16) -> u16 {
+        let [low, page] = addr.to_le_bytes();
+        let target_low = self.bus.read(addr);
+        let target_high = self
+            .bus
+            .read(u16::from_le_bytes([low.wrapping_add(1), page]));
+        u16::from_le_bytes([target_low, target_high])
+    }
+}
+
+fn indexed(base: u16, index: u8) -> (u16, bool) {
+    let address = base.wrapping_add(index as u16);
+    (address, crosses_page(base, address))
+}
+
+fn crosses_page(from: u16, to: u16) -> bool {
+    (from & 0xFF00) != (to & 0xFF00)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Resolves the operand of a two or three byte instruction at $8000
+    fn resolve(mode: AddressingMode, operand: [u8; 2], setup: fn(&mut Cpu6502)) -> Resolved {
+        let mut cpu = Cpu6502::with_program(vec![0xEA, operand[0], operand[1]]);
+        setup(&mut cpu);
+        cpu.fetch_byte();
+        cpu.resolve(mode).unwrap()
+    }
+
+    fn resolved(address: u16, page_crossed: bool) -> Resolved {
+        Resolved {
+            address,
+            page_crossed,
+        }
+    }
+
+    #[test]
+    fn nothing_to_resolve() {
+        let mut cpu = Cpu6502::with_program(vec![0xEA, 0x69]);
+        cpu.fetch_byte();
+
+        for mode in [
+            AddressingMode::Implied,
+            AddressingMode::Accumulator,
+            AddressingMode::Immediate,
+        ] {
+            assert_eq!(cpu.resolve(mode), None);
+        }
+        assert_eq!(cpu.ip, 0x8001);
+    }
+
+    #[test]
+    fn direct_modes() {
+        let zero_page = resolve(AddressingMode::ZeroPage, [0x42, 0x00], |_| {});
+        assert_eq!(zero_page, resolved(0x0042, false));
+
+        let zero_page_x = resolve(AddressingMode::ZeroPageX, [0x42, 0x00], |cpu| cpu.x = 0x01);
+        assert_eq!(zero_page_x, resolved(0x0043, false));
+
+        let absolute = resolve(AddressingMode::Absolute, [0x34, 0x12], |_| {});
+        assert_eq!(absolute, resolved(0x1234, false));
+
+        let absolute_x = resolve(AddressingMode::AbsoluteX, [0x34, 0x12], |cpu| cpu.x = 0x01);
+        assert_eq!(absolute_x, resolved(0x1235, false));
+
+        let absolute_y = resolve(AddressingMode::AbsoluteY, [0xFF, 0x12], |cpu| cpu.y = 0x01);
+        assert_eq!(absolute_y, resolved(0x1300, true));
+    }
+
+    #[test]
+    fn indirect_modes() {
+        let indirect = resolve(AddressingMode::Indirect, [0x00, 0x03], |cpu| {
+            cpu.bus[0x0300] = 0x34;
+            cpu.bus[0x0301] = 0x12;
+        });
+        assert_eq!(indirect, resolved(0x1234, false));
+
+        let indirect_x = resolve(AddressingMode::IndirectX, [0x40, 0x00], |cpu| {
+            cpu.x = 0x02;
+            cpu.bus[0x0042] = 0x34;
+            cpu.bus[0x0043] = 0x12;
+        });
+        assert_eq!(indirect_x, resolved(0x1234, false));
+
+        let indirect_y = resolve(AddressingMode::IndirectY, [0x42, 0x00], |cpu| {
+            cpu.y = 0x10;
+            cpu.bus[0x0042] = 0xF8;
+            cpu.bus[0x0043] = 0x12;
+        });
+        assert_eq!(indirect_y, resolved(0x1308, true));
+    }
+
+    #[test]
+    fn relative() {
+        // offsets count from the instruction after the branch at $8002
+        let forward = resolve(AddressingMode::Relative, [0x10, 0x00], |_| {});
+        assert_eq!(forward, resolved(0x8012, false));
+
+        let backward = resolve(AddressingMode::Relative, [0xFE, 0x00], |_| {});
+        assert_eq!(backward, resolved(0x8000, false));
+
+        let previous_page = resolve(AddressingMode::Relative, [0x80, 0x00], |_| {});
+        assert_eq!(previous_page, resolved(0x7F82, true));
+    }
+}
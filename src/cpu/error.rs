@@ -0,0 +1,191 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::bus::Bus;
+use crate::cpu::{Cpu6502, Instruction, Mnemonic};
+
+// Why the CPU couldn't carry on. Addresses are where the opcode was
+// fetched from, or where the bus failed an access
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuError {
+    UnimplementedOpcode { opcode: u8, address: u16 },
+    Jam { opcode: u8, address: u16 },
+    BusFault { address: u16 },
+}
+
+impl fmt::Display for CpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CpuError::UnimplementedOpcode { opcode, address } => {
+                write!(f, "unimplemented opcode ${opcode:02X} at ${address:04X}")
+            }
+            CpuError::Jam { opcode, address } => {
+                write!(f, "JAM opcode ${opcode:02X} at ${address:04X}")
+            }
+            CpuError::BusFault { address } => write!(f, "bus fault at ${address:04X}"),
+        }
+    }
+}
+
+impl Error for CpuError {}
+
+// What to do about opcodes the emulator has nothing for and accesses the
+// bus reports as faults. Lenient carries on, running such an opcode as a
+// one byte no-op, while strict stops with an error
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    #[default]
+    Lenient,
+    Strict,
+}
+
+impl<B: Bus> Cpu6502<B> {
+    // Under the strict policy, stops on an opcode the CPU won't run or one
+    // that would jam it, returning whether it did. The instruction pointer
+    // is left on the opcode, so running again comes back to the same error
+    // instead of going on past it
+    pub(super) fn refuse_opcode(
+        &mut self,
+        opcode: u8,
+        address: u16,
+        instruction: Option<Instruction>,
+    ) -> bool {
+        if self.errors == ErrorPolicy::Lenient {
+            return false;
+        }
+
+        self.error = match instruction {
+            None => Some(CpuError::UnimplementedOpcode { opcode, address }),
+            Some(instruction) if instruction.mnemonic == Mnemonic::Jam => {
+                Some(CpuError::Jam { opcode, address })
+            }
+            Some(_) => return false,
+        };
+        self.ip = address;
+        true
+    }
+
+    pub(super) fn check_bus_fault(&mut self) {
+        let Some(address) = self.bus.take_fault() else {
+            return;
+        };
+
+        if self.errors == ErrorPolicy::Strict && self.error.is_none() {
+            self.error = Some(CpuError::BusFault { address });
+        }
+    }
+
+    pub(super) fn take_error(&mut self) -> Result<(), CpuError> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bus::MemoryMap;
+    use crate::cpu::{StopReason, Trap};
+
+    // With the unstable opcodes turned off, which leaves ANE ($8B) with
+    // nothing behind it
+    fn without_unstable(program: Vec<u8>) -> Cpu6502 {
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.unstable.enabled = false;
+        cpu
+    }
+
+    fn strict(program: Vec<u8>) -> Cpu6502 {
+        let mut cpu = without_unstable(program);
+        cpu.errors = ErrorPolicy::Strict;
+        cpu
+    }
+
+    #[test]
+    fn lenient_skips_unimplemented_opcodes() {
+        let mut cpu = without_unstable(vec![0x8B, 0xE8, 0xFF]);
+        assert_eq!(cpu.run(), Ok(()));
+        assert_eq!(cpu.x, 0x01);
+    }
+
+    #[test]
+    fn strict_stops_on_unimplemented_opcodes() {
+        let mut cpu = strict(vec![0xE8, 0x8B, 0xE8, 0xFF]);
+
+        let error = CpuError::UnimplementedOpcode {
+            opcode: 0x8B,
+            address: 0x8001,
+        };
+        assert_eq!(cpu.run(), Err(error));
+        assert_eq!(cpu.x, 0x01);
+        assert_eq!(cpu.ip, 0x8001);
+
+        // the CPU is left on the opcode, so it fails the same way again
+        assert_eq!(cpu.step(), Err(error));
+        assert_eq!(cpu.run_for_cycles(100), Err(error));
+        assert_eq!(cpu.x, 0x01);
+        assert_eq!(error.to_string(), "unimplemented opcode $8B at $8001");
+    }
+
+    #[test]
+    fn strict_stops_on_jam() {
+        let mut cpu = strict(vec![0x02, 0xFF]);
+        let error = CpuError::Jam {
+            opcode: 0x02,
+            address: 0x8000,
+        };
+        assert_eq!(cpu.run(), Err(error));
+
+        // a trap policy that halts on JAM comes first
+        cpu.traps.jam = true;
+        assert_eq!(
+            cpu.run_for_cycles(100),
+            Ok(StopReason::Trapped(Trap::Jam(0x02)))
+        );
+    }
+
+    #[test]
+    fn ticks_leave_the_error_for_run() {
+        let mut cpu = strict(vec![0x8B, 0xFF]);
+        cpu.tick();
+
+        assert_eq!(
+            cpu.run(),
+            Err(CpuError::UnimplementedOpcode {
+                opcode: 0x8B,
+                address: 0x8000,
+            })
+        );
+
+        cpu.reset();
+        cpu.errors = ErrorPolicy::Lenient;
+        assert_eq!(cpu.run(), Ok(()));
+    }
+
+    #[test]
+    fn bus_faults() {
+        // STA $4000 with nothing mapped there
+        let mut rom = vec![0xEA; 0x8000];
+        rom[..4].copy_from_slice(&[0x8D, 0x00, 0x40, 0xFF]);
+        rom[0x7FFC] = 0x00;
+        rom[0x7FFD] = 0x80;
+        let map = || {
+            MemoryMap::builder()
+                .ram(0x0000..=0x07FF, 0x0800)
+                .rom(0x8000..=0xFFFF, &rom)
+                .build()
+        };
+
+        let mut cpu = Cpu6502::with_bus(map());
+        cpu.reset();
+        assert_eq!(cpu.run(), Ok(()));
+
+        let mut cpu = Cpu6502::with_bus(map());
+        cpu.errors = ErrorPolicy::Strict;
+        cpu.reset();
+        assert_eq!(cpu.run(), Err(CpuError::BusFault { address: 0x4000 }));
+        assert_eq!(cpu.ip, 0x8003);
+    }
+}
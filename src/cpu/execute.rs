@@ -0,0 +1,526 @@
+use crate::bus::Bus;
+use crate::cpu::opcodes::*;
+use crate::cpu::{lookup, AddressingMode, Cpu6502, Instruction, Mnemonic, Resolved};
+
+// How an instruction uses the operand at its effective address
+pub(super) enum Operation {
+    Read,
+    Write,
+    Modify,
+}
+
+pub(super) fn operation(mnemonic: Mnemonic) -> Operation {
+    use Mnemonic::*;
+
+    match mnemonic {
+        Sta | Stx | Sty | Sax | Sha | Shx | Shy | Tas => Operation::Write,
+        Asl | Lsr | Rol | Ror | Inc | Dec | Slo | Rla | Sre | Rra | Dcp | Isc => Operation::Modify,
+        _ => Operation::Read,
+    }
+}
+
+// SHA, SHX, SHY and TAS store a value that depends on the high byte of the
+// address before indexing
+pub(super) fn stores_high_and(mnemonic: Mnemonic) -> bool {
+    matches!(
+        mnemonic,
+        Mnemonic::Sha | Mnemonic::Shx | Mnemonic::Shy | Mnemonic::Tas
+    )
+}
+
+impl<B: Bus> Cpu6502<B> {
+    // The table entry for the opcode, leaving out the unstable opcodes when
+    // the model turns them off
+    pub(super) fn instruction(&self, opcode: u8) -> Option<Instruction> {
+        lookup(opcode)
+            .filter(|instruction| self.unstable.enabled || !instruction.mnemonic.is_unstable())
+    }
+
+    // Runs a whole instruction from its table entry. The work each
+    // instruction does is shared with the cycle-stepped engine, which only
+    // differs in how it gets the operand there
+    pub(super) fn execute(&mut self, instruction: Instruction) {
+        self.mnemonic = instruction.mnemonic;
+
+        // These move the stack and instruction pointer around in their
+        // own ways, rather than working on an operand
+        match instruction.mnemonic {
+            Mnemonic::Brk => return force_break(self),
+            Mnemonic::Jsr => {
+                jump_subroutine(self);
+                self.resolved = Some(Resolved {
+                    address: self.ip,
+                    page_crossed: false,
+                });
+                return;
+            }
+            Mnemonic::Rts => return return_from_subroutine(self),
+            Mnemonic::Rti => return return_from_interrupt(self),
+            Mnemonic::Pha => return push_a(self),
+            Mnemonic::Php => return push_status(self),
+            Mnemonic::Pla => return pull_a(self),
+            Mnemonic::Plp => return pull_status(self),
+            Mnemonic::Jam => return self.lock_up(),
+            _ => {}
+        }
+
+        let Some(operand) = self.resolve(instruction.mode) else {
+            if instruction.mode == AddressingMode::Immediate {
+                let value = self.fetch_byte();
+                self.execute_read(value);
+            } else {
+                self.execute_implied();
+            }
+            return;
+        };
+        self.pointer = operand.address;
+        self.resolved = Some(operand);
+
+        // A taken branch costs one extra cycle, and a second one if the
+        // target is on a different page than the next instruction
+        if instruction.mode == AddressingMode::Relative {
+            if self.branch_condition() {
+                self.extra_cycles += 1 + operand.page_crossed as u8;
+                self.ip = operand.address;
+            }
+            return;
+        }
+
+        if instruction.mnemonic == Mnemonic::Jmp {
+            self.ip = operand.address;
+            return;
+        }
+
+        match operation(instruction.mnemonic) {
+            Operation::Read => {
+                // Indexed reads take an extra cycle when adding the index
+                // carries into the high byte, since the CPU first reads
+                // from the unfixed address
+                if operand.page_crossed && instruction.page_penalty {
+                    self.extra_cycles += 1;
+                }
+
+                let value = self.read_memory();
+                self.execute_read(value);
+            }
+            Operation::Write => {
+                if stores_high_and(instruction.mnemonic) {
+                    let index = match instruction.mnemonic {
+                        Mnemonic::Shy => self.x,
+                        _ => self.y,
+                    };
+                    let [_, high] = operand.address.wrapping_sub(index as u16).to_le_bytes();
+                    self.store_high_and(high, operand.page_crossed);
+                    self.resolved = Some(Resolved {
+                        address: self.pointer,
+                        ..operand
+                    });
+                }
+
+                self.write_memory(self.stored());
+            }
+            Operation::Modify => self.read_modify_write(Self::execute_modify),
+        }
+    }
+
+    // Works out the value SHA, SHX, SHY or TAS stores from the high byte of
+    // the address before indexing. When indexing crossed a page, the value
+    // also replaces the high byte of the address written to
+    pub(super) fn store_high_and(&mut self, high: u8, page_crossed: bool) {
+        let register = match self.mnemonic {
+            Mnemonic::Sha => self.a & self.x,
+            Mnemonic::Shx => self.x,
+            Mnemonic::Shy => self.y,
+            Mnemonic::Tas => {
+                self.sp = self.a & self.x;
+                self.sp
+            }
+            mnemonic => unreachable!("{mnemonic:?} doesn't store with the high byte"),
+        };
+
+        self.data = store_high_and(self, register, high);
+        if page_crossed {
+            let [low, _] = self.pointer.to_le_bytes();
+            self.pointer = u16::from_le_bytes([low, self.data]);
+        }
+    }
+
+    // JAM locks the NMOS 6502 up until reset. The stuck CPU is modelled as
+    // running the JAM over and over with interrupts ignored, so ticking lets
+    // time go by but nothing else happens, while run and the bounded runs
+    // return as soon as they see it
+    pub(super) fn lock_up(&mut self) {
+        self.ip = self.ip.wrapping_sub(1);
+        self.jammed = true;
+    }
+
+    pub(super) fn stored(&self) -> u8 {
+        match self.mnemonic {
+            Mnemonic::Sta => self.a,
+            Mnemonic::Stx => self.x,
+            Mnemonic::Sty => self.y,
+            Mnemonic::Sax => self.a & self.x,
+            Mnemonic::Sha | Mnemonic::Shx | Mnemonic::Shy | Mnemonic::Tas => self.data,
+            mnemonic => unreachable!("{mnemonic:?} doesn't store a register"),
+        }
+    }
+
+    pub(super) fn branch_condition(&self) -> bool {
+        match self.mnemonic {
+            Mnemonic::Bpl => !self.flags.negative,
+            Mnemonic::Bmi => self.flags.negative,
+            Mnemonic::Bvc => !self.flags.overflow,
+            Mnemonic::Bvs => self.flags.overflow,
+            Mnemonic::Bcc => !self.flags.carry,
+            Mnemonic::Bcs => self.flags.carry,
+            Mnemonic::Bne => !self.flags.zero,
+            Mnemonic::Beq => self.flags.zero,
+            mnemonic => unreachable!("{mnemonic:?} isn't a branch"),
+        }
+    }
+
+    pub(super) fn execute_implied(&mut self) {
+        match self.mnemonic {
+            Mnemonic::Asl => shift_left_accumulator(self),
+            Mnemonic::Lsr => shift_right_accumulator(self),
+            Mnemonic::Rol => rotate_left_accumulator(self),
+            Mnemonic::Ror => rotate_right_accumulator(self),
+            Mnemonic::Clc => clear_carry(self),
+            Mnemonic::Sec => set_carry(self),
+            Mnemonic::Cli => clear_interrupt_disable(self),
+            Mnemonic::Sei => set_interrupt_disable(self),
+            Mnemonic::Cld => clear_decimal(self),
+            Mnemonic::Sed => set_decimal(self),
+            Mnemonic::Clv => clear_overflow(self),
+            Mnemonic::Dex => decrement_x(self),
+            Mnemonic::Dey => decrement_y(self),
+            Mnemonic::Inx => increment_x(self),
+            Mnemonic::Iny => increment_y(self),
+            Mnemonic::Tax => transfer_a_to_x(self),
+            Mnemonic::Tay => transfer_a_to_y(self),
+            Mnemonic::Txa => transfer_x_to_a(self),
+            Mnemonic::Tya => transfer_y_to_a(self),
+            Mnemonic::Tsx => transfer_sp_to_x(self),
+            Mnemonic::Txs => transfer_x_to_sp(self),
+            Mnemonic::Nop => no_operation(self),
+            mnemonic => unreachable!("{mnemonic:?} isn't implied"),
+        }
+    }
+
+    pub(super) fn execute_read(&mut self, value: u8) {
+        match self.mnemonic {
+            Mnemonic::Lda => load_a(self, value),
+            Mnemonic::Ldx => load_x(self, value),
+            Mnemonic::Ldy => load_y(self, value),
+            Mnemonic::Adc => add(self, value),
+            Mnemonic::Sbc => subtract(self, value),
+            Mnemonic::And => and(self, value),
+            Mnemonic::Ora => or(self, value),
+            Mnemonic::Eor => exclusive_or(self, value),
+            Mnemonic::Bit => bit_test(self, value),
+            Mnemonic::Cmp => compare(self, self.a, value),
+            Mnemonic::Cpx => compare(self, self.x, value),
+            Mnemonic::Cpy => compare(self, self.y, value),
+            Mnemonic::Nop => {}
+            Mnemonic::Lax => load_a_and_x(self, value),
+            Mnemonic::Anc => and_carry(self, value),
+            Mnemonic::Alr => and_shift_right(self, value),
+            Mnemonic::Arr => and_rotate_right(self, value),
+            Mnemonic::Sbx => and_x_subtract(self, value),
+            Mnemonic::Ane => and_x_magic(self, value),
+            Mnemonic::Lxa => load_a_and_x_magic(self, value),
+            Mnemonic::Las => load_and_stack(self, value),
+            mnemonic => unreachable!("{mnemonic:?} doesn't read memory"),
+        }
+    }
+
+    pub(super) fn execute_modify(&mut self, value: u8) -> u8 {
+        match self.mnemonic {
+            Mnemonic::Asl => shift_left(self, value),
+            Mnemonic::Lsr => shift_right(self, value),
+            Mnemonic::Rol => rotate_left(self, value),
+            Mnemonic::Ror => rotate_right(self, value),
+            Mnemonic::Inc => increment(self, value),
+            Mnemonic::Dec => decrement(self, value),
+            Mnemonic::Slo => shift_left_or(self, value),
+            Mnemonic::Rla => rotate_left_and(self, value),
+            Mnemonic::Sre => shift_right_exclusive_or(self, value),
+            Mnemonic::Rra => rotate_right_add(self, value),
+            Mnemonic::Dcp => decrement_compare(self, value),
+            Mnemonic::Isc => increment_subtract(self, value),
+            mnemonic => unreachable!("{mnemonic:?} doesn't modify memory"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cpu::Cpu6502;
+
+    #[test]
+    fn store_a_zeropage() {
+        let program: Vec<u8> = vec![0xA9, 0x69, 0x85, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x42], 0x69);
+    }
+
+    #[test]
+    fn store_a_zeropage_x() {
+        let program: Vec<u8> = vec![0x95, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x69;
+        cpu.x = 0x01;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x01], 0x69);
+
+        // zero page indexing wraps around instead of leaving page zero
+        let program: Vec<u8> = vec![0x95, 0xFF, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x69;
+        cpu.x = 0x02;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x01], 0x69);
+        assert_eq!(cpu.bus[0x0101], 0x00);
+    }
+
+    #[test]
+    fn store_a_absolute() {
+        let program: Vec<u8> = vec![0x8D, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x69;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x69);
+    }
+
+    #[test]
+    fn store_a_absolute_x() {
+        let program: Vec<u8> = vec![0x9D, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x69;
+        cpu.x = 0x01;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x69);
+
+        cpu.x = 0x98;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4300], 0x69);
+    }
+
+    #[test]
+    fn store_a_absolute_y() {
+        let program: Vec<u8> = vec![0x99, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x69;
+        cpu.y = 0x01;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x69);
+
+        cpu.y = 0x98;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4300], 0x69);
+    }
+
+    #[test]
+    fn store_a_indirect_x() {
+        let program: Vec<u8> = vec![0x81, 0x10, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x69;
+        cpu.x = 0x04;
+        cpu.bus[0x14] = 0x69;
+        cpu.bus[0x15] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x69);
+    }
+
+    #[test]
+    fn store_a_indirect_y() {
+        let program: Vec<u8> = vec![0x91, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x69;
+        cpu.y = 0x01;
+        cpu.bus[0x0000] = 0x68;
+        cpu.bus[0x0001] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x69);
+
+        cpu.y = 0x98;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4300], 0x69);
+    }
+
+    #[test]
+    fn store_a_indirect_y_page_cross() {
+        // writes always take the extra cycle, so crossing costs nothing
+        let mut cpu = Cpu6502::with_program(vec![0x91, 0xFF, 0xFF]);
+        cpu.bus[0x00FF] = 0xFF;
+        cpu.bus[0x0000] = 0x42;
+        cpu.a = 0x69;
+        cpu.y = 0x01;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x4300], 0x69);
+        assert_eq!(cpu.bus[0x4200], 0x00);
+        assert_eq!(cpu.cycles, 6);
+    }
+
+    #[test]
+    fn store_x_zeropage() {
+        let program: Vec<u8> = vec![0xA2, 0x69, 0x86, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x42], 0x69);
+    }
+
+    #[test]
+    fn store_x_zeropage_y() {
+        let program: Vec<u8> = vec![0x96, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x69;
+        cpu.y = 0x01;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x01], 0x69);
+
+        cpu.y = 0x00;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x00], 0x69);
+    }
+
+    #[test]
+    fn store_x_absolute() {
+        let program: Vec<u8> = vec![0x8E, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x69;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x69);
+    }
+
+    #[test]
+    fn store_y_zeropage() {
+        let program: Vec<u8> = vec![0xA0, 0x69, 0x84, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x42], 0x69);
+    }
+
+    #[test]
+    fn store_y_zeropage_x() {
+        let program: Vec<u8> = vec![0x94, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.y = 0x69;
+        cpu.x = 0x01;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x01], 0x69);
+
+        cpu.x = 0x00;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x00], 0x69);
+    }
+
+    #[test]
+    fn store_y_absolute() {
+        let program: Vec<u8> = vec![0x8C, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.y = 0x69;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x69);
+    }
+
+    type SetCondition = fn(&mut Cpu6502, bool);
+
+    fn execute_one(cpu: &mut Cpu6502) {
+        let opcode = cpu.fetch_byte();
+        cpu.decode(opcode);
+    }
+
+    #[test]
+    fn branch_conditions() {
+        let cases: [(u8, SetCondition); 8] = [
+            (0x10, |cpu, taken| cpu.flags.negative = !taken),
+            (0x30, |cpu, taken| cpu.flags.negative = taken),
+            (0x50, |cpu, taken| cpu.flags.overflow = !taken),
+            (0x70, |cpu, taken| cpu.flags.overflow = taken),
+            (0x90, |cpu, taken| cpu.flags.carry = !taken),
+            (0xB0, |cpu, taken| cpu.flags.carry = taken),
+            (0xD0, |cpu, taken| cpu.flags.zero = !taken),
+            (0xF0, |cpu, taken| cpu.flags.zero = taken),
+        ];
+
+        for (opcode, set_condition) in cases {
+            let program: Vec<u8> = vec![opcode, 0x10, 0xFF];
+            let mut cpu = Cpu6502::with_program(program);
+
+            set_condition(&mut cpu, true);
+            execute_one(&mut cpu);
+            assert_eq!(cpu.ip, 0x8012, "{opcode:02X} taken");
+            assert_eq!(cpu.extra_cycles, 1);
+
+            cpu.reset();
+            set_condition(&mut cpu, false);
+            execute_one(&mut cpu);
+            assert_eq!(cpu.ip, 0x8002, "{opcode:02X} not taken");
+            assert_eq!(cpu.extra_cycles, 0);
+        }
+    }
+
+    #[test]
+    fn branch_backwards() {
+        let program: Vec<u8> = vec![
+            0xA2, 0x05, // LDX #$05
+            0xC8, // INY
+            0xCA, // DEX
+            0xD0, 0xFC, // BNE -4
+            0xFF,
+        ];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.x, 0x00);
+        assert_eq!(cpu.y, 0x05);
+    }
+
+    #[test]
+    fn branch_across_page() {
+        let program: Vec<u8> = vec![0xD0, 0xFC, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        execute_one(&mut cpu);
+        assert_eq!(cpu.ip, 0x7FFE);
+        assert_eq!(cpu.extra_cycles, 2);
+
+        let mut program: Vec<u8> = vec![0xEA; 0x100];
+        program[0xFC] = 0xD0;
+        program[0xFD] = 0x01;
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.ip = 0x80FC;
+        execute_one(&mut cpu);
+        assert_eq!(cpu.ip, 0x80FF);
+        assert_eq!(cpu.extra_cycles, 1);
+
+        cpu.ip = 0x80FC;
+        cpu.bus[0x80FD] = 0x02;
+        execute_one(&mut cpu);
+        assert_eq!(cpu.ip, 0x8100);
+        assert_eq!(cpu.extra_cycles, 2);
+    }
+}
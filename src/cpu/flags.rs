@@ -1,16 +1,96 @@
-#[derive(Debug, Default)]
+use std::fmt;
+
+// The break and unused bits have no storage in the real status register
+// and only mean something in a copy of it pushed to the stack, but they
+// are kept here so a byte survives a round trip through from_byte
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Flags {
     pub carry: bool,
     pub zero: bool,
     pub interrupt_disable: bool,
     pub decimal: bool,
-    pub bit0: bool,
-    pub bit1: bool,
+    pub break_command: bool,
+    pub unused: bool,
     pub overflow: bool,
     pub negative: bool,
 }
 
 impl Flags {
+    // The bit of each flag in the status register
+    pub const CARRY: u8 = 0b0000_0001;
+    pub const ZERO: u8 = 0b0000_0010;
+    pub const INTERRUPT_DISABLE: u8 = 0b0000_0100;
+    pub const DECIMAL: u8 = 0b0000_1000;
+    pub const BREAK: u8 = 0b0001_0000;
+    pub const UNUSED: u8 = 0b0010_0000;
+    pub const OVERFLOW: u8 = 0b0100_0000;
+    pub const NEGATIVE: u8 = 0b1000_0000;
+
+    pub fn from_byte(status: u8) -> Self {
+        Flags {
+            carry: status & Self::CARRY != 0,
+            zero: status & Self::ZERO != 0,
+            interrupt_disable: status & Self::INTERRUPT_DISABLE != 0,
+            decimal: status & Self::DECIMAL != 0,
+            break_command: status & Self::BREAK != 0,
+            unused: status & Self::UNUSED != 0,
+            overflow: status & Self::OVERFLOW != 0,
+            negative: status & Self::NEGATIVE != 0,
+        }
+    }
+
+    // Packs the flags into the NV-BDIZC layout of the status register
+    pub fn to_byte(&self) -> u8 {
+        (self.carry as u8)
+            | (self.zero as u8) << 1
+            | (self.interrupt_disable as u8) << 2
+            | (self.decimal as u8) << 3
+            | (self.break_command as u8) << 4
+            | (self.unused as u8) << 5
+            | (self.overflow as u8) << 6
+            | (self.negative as u8) << 7
+    }
+
+    // The status as pushed to the stack. The unused bit is always set, and
+    // the break bit is set by BRK and PHP but clear for IRQ and NMI, which
+    // is the only way a handler can tell them apart
+    pub fn pushed(&self, break_command: bool) -> u8 {
+        let status = (self.to_byte() & !Self::BREAK) | Self::UNUSED;
+        if break_command {
+            status | Self::BREAK
+        } else {
+            status
+        }
+    }
+
+    // PLP and RTI take every flag from the pulled status except the break
+    // and unused bits, which only exist on the stack
+    pub fn pull(&mut self, status: u8) {
+        let kept = self.to_byte() & (Self::BREAK | Self::UNUSED);
+        *self = Flags::from_byte((status & !(Self::BREAK | Self::UNUSED)) | kept);
+    }
+
+    // The flags that differ between the two, such as before and after an
+    // instruction, set in the result
+    pub fn changed(&self, other: &Flags) -> Flags {
+        Flags::from_byte(self.to_byte() ^ other.to_byte())
+    }
+
+    // Most instructions set zero and negative from the value they produce
+    pub fn update_zero_and_negative(&mut self, value: u8) {
+        if value == 0 {
+            self.set_zero();
+        } else {
+            self.clear_zero();
+        }
+
+        if (value & (1 << 7)) != 0 {
+            self.set_negative();
+        } else {
+            self.clear_negative();
+        }
+    }
+
     pub fn set_carry(&mut self) {
         self.carry = true;
     }
@@ -43,20 +123,20 @@ impl Flags {
         self.decimal = false;
     }
 
-    pub fn set_bit0(&mut self) {
-        self.bit0 = true;
+    pub fn set_break_command(&mut self) {
+        self.break_command = true;
     }
 
-    pub fn clear_bit0(&mut self) {
-        self.bit0 = false;
+    pub fn clear_break_command(&mut self) {
+        self.break_command = false;
     }
 
-    pub fn set_bit1(&mut self) {
-        self.bit1 = true;
+    pub fn set_unused(&mut self) {
+        self.unused = true;
     }
 
-    pub fn clear_bit1(&mut self) {
-        self.bit1 = false;
+    pub fn clear_unused(&mut self) {
+        self.unused = false;
     }
 
     pub fn set_overflow(&mut self) {
@@ -75,3 +155,83 @@ impl Flags {
         self.negative = false;
     }
 }
+
+// Shows each flag as its letter in NV-BDIZC order, in uppercase when set
+// and lowercase when clear. The unused bit is always shown as a dash
+impl fmt::Display for Flags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = self.to_byte();
+
+        for (bit, letter) in "NV-BDIZC".chars().enumerate() {
+            let set = status & (0x80 >> bit) != 0;
+            let shown = match letter {
+                '-' => '-',
+                _ if set => letter,
+                _ => letter.to_ascii_lowercase(),
+            };
+            write!(f, "{shown}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn byte_layout() {
+        let flags = Flags {
+            carry: true,
+            decimal: true,
+            negative: true,
+            ..Flags::default()
+        };
+        assert_eq!(flags.to_byte(), 0b1000_1001);
+
+        for status in 0..=0xFF {
+            assert_eq!(Flags::from_byte(status).to_byte(), status);
+        }
+    }
+
+    #[test]
+    fn pushed_status() {
+        let flags = Flags::from_byte(0b1100_0011);
+        assert_eq!(flags.pushed(true), 0b1111_0011);
+        assert_eq!(flags.pushed(false), 0b1110_0011);
+
+        // bits already set in the flags don't leak into the pushed copy
+        let flags = Flags::from_byte(0b0001_0000);
+        assert_eq!(flags.pushed(false), 0b0010_0000);
+    }
+
+    #[test]
+    fn pulled_status() {
+        let mut flags = Flags::default();
+        flags.pull(0xFF);
+        assert_eq!(flags.to_byte(), 0b1100_1111);
+
+        flags.pull(0x00);
+        assert_eq!(flags, Flags::default());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Flags::from_byte(0b1101_0101).to_string(), "NV-BdIzC");
+        assert_eq!(Flags::from_byte(0x00).to_string(), "nv-bdizc");
+        assert_eq!(Flags::from_byte(0xFF).to_string(), "NV-BDIZC");
+    }
+
+    #[test]
+    fn changed_flags() {
+        let before = Flags::from_byte(0b1000_0011);
+        let after = Flags::from_byte(0b0000_0001);
+
+        let changed = before.changed(&after);
+        assert!(changed.negative);
+        assert!(changed.zero);
+        assert!(!changed.carry);
+        assert_eq!(changed.to_byte(), 0b1000_0010);
+    }
+}
@@ -0,0 +1,923 @@
+use std::fmt;
+
+// The instruction set as data: what each opcode does, how it finds its
+// operand and how long it takes. Execution, cycle counting and disassembly
+// all work from this one table
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mnemonic {
+    Adc,
+    And,
+    Asl,
+    Bcc,
+    Bcs,
+    Beq,
+    Bit,
+    Bmi,
+    Bne,
+    Bpl,
+    Brk,
+    Bvc,
+    Bvs,
+    Clc,
+    Cld,
+    Cli,
+    Clv,
+    Cmp,
+    Cpx,
+    Cpy,
+    Dec,
+    Dex,
+    Dey,
+    Eor,
+    Inc,
+    Inx,
+    Iny,
+    Jmp,
+    Jsr,
+    Lda,
+    Ldx,
+    Ldy,
+    Lsr,
+    Nop,
+    Ora,
+    Pha,
+    Php,
+    Pla,
+    Plp,
+    Rol,
+    Ror,
+    Rti,
+    Rts,
+    Sbc,
+    Sec,
+    Sed,
+    Sei,
+    Sta,
+    Stx,
+    Sty,
+    Tax,
+    Tay,
+    Tsx,
+    Txa,
+    Txs,
+    Tya,
+
+    // Undocumented
+    Alr,
+    Anc,
+    Ane,
+    Arr,
+    Dcp,
+    Isc,
+    Jam,
+    Las,
+    Lax,
+    Lxa,
+    Rla,
+    Rra,
+    Sax,
+    Sbx,
+    Sha,
+    Shx,
+    Shy,
+    Slo,
+    Sre,
+    Tas,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressingMode {
+    Implied,
+    Accumulator,
+    Immediate,
+    ZeroPage,
+    ZeroPageX,
+    ZeroPageY,
+    Absolute,
+    AbsoluteX,
+    AbsoluteY,
+    Indirect,
+    IndirectX,
+    IndirectY,
+    Relative,
+}
+
+impl Mnemonic {
+    // Whether this is one of the undocumented instructions whose behaviour
+    // varies between chips
+    pub fn is_unstable(self) -> bool {
+        use Mnemonic::*;
+
+        matches!(self, Ane | Lxa | Sha | Shx | Shy | Tas | Las)
+    }
+}
+
+impl AddressingMode {
+    // Length in bytes of an instruction using this mode, opcode included
+    pub const fn length(self) -> u8 {
+        use AddressingMode::*;
+
+        match self {
+            Implied | Accumulator => 1,
+            Immediate | ZeroPage | ZeroPageX | ZeroPageY | IndirectX | IndirectY | Relative => 2,
+            Absolute | AbsoluteX | AbsoluteY | Indirect => 3,
+        }
+    }
+}
+
+// An entry in the opcode table. The base cycle count leaves out the extra
+// cycle for crossing a page, which only instructions with a page penalty
+// pay, and the extra cycles for taking a branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub mnemonic: Mnemonic,
+    pub mode: AddressingMode,
+    pub length: u8,
+    pub cycles: u8,
+    pub page_penalty: bool,
+}
+
+// Every opcode of the NMOS 6502, undocumented ones included. The table is
+// full, but lookups still allow for opcodes the CPU is set up not to run
+pub static OPCODES: [Option<Instruction>; 256] = {
+    use AddressingMode::*;
+    use Mnemonic::*;
+
+    const fn op(
+        mnemonic: Mnemonic,
+        mode: AddressingMode,
+        cycles: u8,
+        page_penalty: bool,
+    ) -> Option<Instruction> {
+        Some(Instruction {
+            mnemonic,
+            mode,
+            length: mode.length(),
+            cycles,
+            page_penalty,
+        })
+    }
+
+    let mut table = [None; 256];
+    table[0x00] = op(Brk, Implied, 7, false);
+    table[0x01] = op(Ora, IndirectX, 6, false);
+    table[0x05] = op(Ora, ZeroPage, 3, false);
+    table[0x06] = op(Asl, ZeroPage, 5, false);
+    table[0x08] = op(Php, Implied, 3, false);
+    table[0x09] = op(Ora, Immediate, 2, false);
+    table[0x0A] = op(Asl, Accumulator, 2, false);
+    table[0x0D] = op(Ora, Absolute, 4, false);
+    table[0x0E] = op(Asl, Absolute, 6, false);
+    table[0x10] = op(Bpl, Relative, 2, false);
+    table[0x11] = op(Ora, IndirectY, 5, true);
+    table[0x15] = op(Ora, ZeroPageX, 4, false);
+    table[0x16] = op(Asl, ZeroPageX, 6, false);
+    table[0x18] = op(Clc, Implied, 2, false);
+    table[0x19] = op(Ora, AbsoluteY, 4, true);
+    table[0x1D] = op(Ora, AbsoluteX, 4, true);
+    table[0x1E] = op(Asl, AbsoluteX, 7, false);
+    table[0x20] = op(Jsr, Absolute, 6, false);
+    table[0x21] = op(And, IndirectX, 6, false);
+    table[0x24] = op(Bit, ZeroPage, 3, false);
+    table[0x25] = op(And, ZeroPage, 3, false);
+    table[0x26] = op(Rol, ZeroPage, 5, false);
+    table[0x28] = op(Plp, Implied, 4, false);
+    table[0x29] = op(And, Immediate, 2, false);
+    table[0x2A] = op(Rol, Accumulator, 2, false);
+    table[0x2C] = op(Bit, Absolute, 4, false);
+    table[0x2D] = op(And, Absolute, 4, false);
+    table[0x2E] = op(Rol, Absolute, 6, false);
+    table[0x30] = op(Bmi, Relative, 2, false);
+    table[0x31] = op(And, IndirectY, 5, true);
+    table[0x35] = op(And, ZeroPageX, 4, false);
+    table[0x36] = op(Rol, ZeroPageX, 6, false);
+    table[0x38] = op(Sec, Implied, 2, false);
+    table[0x39] = op(And, AbsoluteY, 4, true);
+    table[0x3D] = op(And, AbsoluteX, 4, true);
+    table[0x3E] = op(Rol, AbsoluteX, 7, false);
+    table[0x40] = op(Rti, Implied, 6, false);
+    table[0x41] = op(Eor, IndirectX, 6, false);
+    table[0x45] = op(Eor, ZeroPage, 3, false);
+    table[0x46] = op(Lsr, ZeroPage, 5, false);
+    table[0x48] = op(Pha, Implied, 3, false);
+    table[0x49] = op(Eor, Immediate, 2, false);
+    table[0x4A] = op(Lsr, Accumulator, 2, false);
+    table[0x4C] = op(Jmp, Absolute, 3, false);
+    table[0x4D] = op(Eor, Absolute, 4, false);
+    table[0x4E] = op(Lsr, Absolute, 6, false);
+    table[0x50] = op(Bvc, Relative, 2, false);
+    table[0x51] = op(Eor, IndirectY, 5, true);
+    table[0x55] = op(Eor, ZeroPageX, 4, false);
+    table[0x56] = op(Lsr, ZeroPageX, 6, false);
+    table[0x58] = op(Cli, Implied, 2, false);
+    table[0x59] = op(Eor, AbsoluteY, 4, true);
+    table[0x5D] = op(Eor, AbsoluteX, 4, true);
+    table[0x5E] = op(Lsr, AbsoluteX, 7, false);
+    table[0x60] = op(Rts, Implied, 6, false);
+    table[0x61] = op(Adc, IndirectX, 6, false);
+    table[0x65] = op(Adc, ZeroPage, 3, false);
+    table[0x66] = op(Ror, ZeroPage, 5, false);
+    table[0x68] = op(Pla, Implied, 4, false);
+    table[0x69] = op(Adc, Immediate, 2, false);
+    table[0x6A] = op(Ror, Accumulator, 2, false);
+    table[0x6C] = op(Jmp, Indirect, 5, false);
+    table[0x6D] = op(Adc, Absolute, 4, false);
+    table[0x6E] = op(Ror, Absolute, 6, false);
+    table[0x70] = op(Bvs, Relative, 2, false);
+    table[0x71] = op(Adc, IndirectY, 5, true);
+    table[0x75] = op(Adc, ZeroPageX, 4, false);
+    table[0x76] = op(Ror, ZeroPageX, 6, false);
+    table[0x78] = op(Sei, Implied, 2, false);
+    table[0x79] = op(Adc, AbsoluteY, 4, true);
+    table[0x7D] = op(Adc, AbsoluteX, 4, true);
+    table[0x7E] = op(Ror, AbsoluteX, 7, false);
+    table[0x81] = op(Sta, IndirectX, 6, false);
+    table[0x84] = op(Sty, ZeroPage, 3, false);
+    table[0x85] = op(Sta, ZeroPage, 3, false);
+    table[0x86] = op(Stx, ZeroPage, 3, false);
+    table[0x88] = op(Dey, Implied, 2, false);
+    table[0x8A] = op(Txa, Implied, 2, false);
+    table[0x8C] = op(Sty, Absolute, 4, false);
+    table[0x8D] = op(Sta, Absolute, 4, false);
+    table[0x8E] = op(Stx, Absolute, 4, false);
+    table[0x90] = op(Bcc, Relative, 2, false);
+    table[0x91] = op(Sta, IndirectY, 6, false);
+    table[0x94] = op(Sty, ZeroPageX, 4, false);
+    table[0x95] = op(Sta, ZeroPageX, 4, false);
+    table[0x96] = op(Stx, ZeroPageY, 4, false);
+    table[0x98] = op(Tya, Implied, 2, false);
+    table[0x99] = op(Sta, AbsoluteY, 5, false);
+    table[0x9A] = op(Txs, Implied, 2, false);
+    table[0x9D] = op(Sta, AbsoluteX, 5, false);
+    table[0xA0] = op(Ldy, Immediate, 2, false);
+    table[0xA1] = op(Lda, IndirectX, 6, false);
+    table[0xA2] = op(Ldx, Immediate, 2, false);
+    table[0xA4] = op(Ldy, ZeroPage, 3, false);
+    table[0xA5] = op(Lda, ZeroPage, 3, false);
+    table[0xA6] = op(Ldx, ZeroPage, 3, false);
+    table[0xA8] = op(Tay, Implied, 2, false);
+    table[0xA9] = op(Lda, Immediate, 2, false);
+    table[0xAA] = op(Tax, Implied, 2, false);
+    table[0xAC] = op(Ldy, Absolute, 4, false);
+    table[0xAD] = op(Lda, Absolute, 4, false);
+    table[0xAE] = op(Ldx, Absolute, 4, false);
+    table[0xB0] = op(Bcs, Relative, 2, false);
+    table[0xB1] = op(Lda, IndirectY, 5, true);
+    table[0xB4] = op(Ldy, ZeroPageX, 4, false);
+    table[0xB5] = op(Lda, ZeroPageX, 4, false);
+    table[0xB6] = op(Ldx, ZeroPageY, 4, false);
+    table[0xB8] = op(Clv, Implied, 2, false);
+    table[0xB9] = op(Lda, AbsoluteY, 4, true);
+    table[0xBA] = op(Tsx, Implied, 2, false);
+    table[0xBC] = op(Ldy, AbsoluteX, 4, true);
+    table[0xBD] = op(Lda, AbsoluteX, 4, true);
+    table[0xBE] = op(Ldx, AbsoluteY, 4, true);
+    table[0xC0] = op(Cpy, Immediate, 2, false);
+    table[0xC1] = op(Cmp, IndirectX, 6, false);
+    table[0xC4] = op(Cpy, ZeroPage, 3, false);
+    table[0xC5] = op(Cmp, ZeroPage, 3, false);
+    table[0xC6] = op(Dec, ZeroPage, 5, false);
+    table[0xC8] = op(Iny, Implied, 2, false);
+    table[0xC9] = op(Cmp, Immediate, 2, false);
+    table[0xCA] = op(Dex, Implied, 2, false);
+    table[0xCC] = op(Cpy, Absolute, 4, false);
+    table[0xCD] = op(Cmp, Absolute, 4, false);
+    table[0xCE] = op(Dec, Absolute, 6, false);
+    table[0xD0] = op(Bne, Relative, 2, false);
+    table[0xD1] = op(Cmp, IndirectY, 5, true);
+    table[0xD5] = op(Cmp, ZeroPageX, 4, false);
+    table[0xD6] = op(Dec, ZeroPageX, 6, false);
+    table[0xD8] = op(Cld, Implied, 2, false);
+    table[0xD9] = op(Cmp, AbsoluteY, 4, true);
+    table[0xDD] = op(Cmp, AbsoluteX, 4, true);
+    table[0xDE] = op(Dec, AbsoluteX, 7, false);
+    table[0xE0] = op(Cpx, Immediate, 2, false);
+    table[0xE1] = op(Sbc, IndirectX, 6, false);
+    table[0xE4] = op(Cpx, ZeroPage, 3, false);
+    table[0xE5] = op(Sbc, ZeroPage, 3, false);
+    table[0xE6] = op(Inc, ZeroPage, 5, false);
+    table[0xE8] = op(Inx, Implied, 2, false);
+    table[0xE9] = op(Sbc, Immediate, 2, false);
+    table[0xEA] = op(Nop, Implied, 2, false);
+    table[0xEC] = op(Cpx, Absolute, 4, false);
+    table[0xED] = op(Sbc, Absolute, 4, false);
+    table[0xEE] = op(Inc, Absolute, 6, false);
+    table[0xF0] = op(Beq, Relative, 2, false);
+    table[0xF1] = op(Sbc, IndirectY, 5, true);
+    table[0xF5] = op(Sbc, ZeroPageX, 4, false);
+    table[0xF6] = op(Inc, ZeroPageX, 6, false);
+    table[0xF8] = op(Sed, Implied, 2, false);
+    table[0xF9] = op(Sbc, AbsoluteY, 4, true);
+    table[0xFD] = op(Sbc, AbsoluteX, 4, true);
+    table[0xFE] = op(Inc, AbsoluteX, 7, false);
+
+    // Undocumented, but stable across NMOS chips. JAM locks the CPU up
+    table[0x02] = op(Jam, Implied, 2, false);
+    table[0x03] = op(Slo, IndirectX, 8, false);
+    table[0x04] = op(Nop, ZeroPage, 3, false);
+    table[0x07] = op(Slo, ZeroPage, 5, false);
+    table[0x0B] = op(Anc, Immediate, 2, false);
+    table[0x0C] = op(Nop, Absolute, 4, false);
+    table[0x0F] = op(Slo, Absolute, 6, false);
+    table[0x12] = op(Jam, Implied, 2, false);
+    table[0x13] = op(Slo, IndirectY, 8, false);
+    table[0x14] = op(Nop, ZeroPageX, 4, false);
+    table[0x17] = op(Slo, ZeroPageX, 6, false);
+    table[0x1A] = op(Nop, Implied, 2, false);
+    table[0x1B] = op(Slo, AbsoluteY, 7, false);
+    table[0x1C] = op(Nop, AbsoluteX, 4, true);
+    table[0x1F] = op(Slo, AbsoluteX, 7, false);
+    table[0x22] = op(Jam, Implied, 2, false);
+    table[0x23] = op(Rla, IndirectX, 8, false);
+    table[0x27] = op(Rla, ZeroPage, 5, false);
+    table[0x2B] = op(Anc, Immediate, 2, false);
+    table[0x2F] = op(Rla, Absolute, 6, false);
+    table[0x32] = op(Jam, Implied, 2, false);
+    table[0x33] = op(Rla, IndirectY, 8, false);
+    table[0x34] = op(Nop, ZeroPageX, 4, false);
+    table[0x37] = op(Rla, ZeroPageX, 6, false);
+    table[0x3A] = op(Nop, Implied, 2, false);
+    table[0x3B] = op(Rla, AbsoluteY, 7, false);
+    table[0x3C] = op(Nop, AbsoluteX, 4, true);
+    table[0x3F] = op(Rla, AbsoluteX, 7, false);
+    table[0x42] = op(Jam, Implied, 2, false);
+    table[0x43] = op(Sre, IndirectX, 8, false);
+    table[0x44] = op(Nop, ZeroPage, 3, false);
+    table[0x47] = op(Sre, ZeroPage, 5, false);
+    table[0x4B] = op(Alr, Immediate, 2, false);
+    table[0x4F] = op(Sre, Absolute, 6, false);
+    table[0x52] = op(Jam, Implied, 2, false);
+    table[0x53] = op(Sre, IndirectY, 8, false);
+    table[0x54] = op(Nop, ZeroPageX, 4, false);
+    table[0x57] = op(Sre, ZeroPageX, 6, false);
+    table[0x5A] = op(Nop, Implied, 2, false);
+    table[0x5B] = op(Sre, AbsoluteY, 7, false);
+    table[0x5C] = op(Nop, AbsoluteX, 4, true);
+    table[0x5F] = op(Sre, AbsoluteX, 7, false);
+    table[0x62] = op(Jam, Implied, 2, false);
+    table[0x63] = op(Rra, IndirectX, 8, false);
+    table[0x64] = op(Nop, ZeroPage, 3, false);
+    table[0x67] = op(Rra, ZeroPage, 5, false);
+    table[0x6B] = op(Arr, Immediate, 2, false);
+    table[0x6F] = op(Rra, Absolute, 6, false);
+    table[0x72] = op(Jam, Implied, 2, false);
+    table[0x73] = op(Rra, IndirectY, 8, false);
+    table[0x74] = op(Nop, ZeroPageX, 4, false);
+    table[0x77] = op(Rra, ZeroPageX, 6, false);
+    table[0x7A] = op(Nop, Implied, 2, false);
+    table[0x7B] = op(Rra, AbsoluteY, 7, false);
+    table[0x7C] = op(Nop, AbsoluteX, 4, true);
+    table[0x7F] = op(Rra, AbsoluteX, 7, false);
+    table[0x80] = op(Nop, Immediate, 2, false);
+    table[0x82] = op(Nop, Immediate, 2, false);
+    table[0x83] = op(Sax, IndirectX, 6, false);
+    table[0x87] = op(Sax, ZeroPage, 3, false);
+    table[0x89] = op(Nop, Immediate, 2, false);
+    table[0x8F] = op(Sax, Absolute, 4, false);
+    table[0x92] = op(Jam, Implied, 2, false);
+    table[0x97] = op(Sax, ZeroPageY, 4, false);
+    table[0xA3] = op(Lax, IndirectX, 6, false);
+    table[0xA7] = op(Lax, ZeroPage, 3, false);
+    table[0xAF] = op(Lax, Absolute, 4, false);
+    table[0xB2] = op(Jam, Implied, 2, false);
+    table[0xB3] = op(Lax, IndirectY, 5, true);
+    table[0xB7] = op(Lax, ZeroPageY, 4, false);
+    table[0xBF] = op(Lax, AbsoluteY, 4, true);
+    table[0xC2] = op(Nop, Immediate, 2, false);
+    table[0xC3] = op(Dcp, IndirectX, 8, false);
+    table[0xC7] = op(Dcp, ZeroPage, 5, false);
+    table[0xCB] = op(Sbx, Immediate, 2, false);
+    table[0xCF] = op(Dcp, Absolute, 6, false);
+    table[0xD2] = op(Jam, Implied, 2, false);
+    table[0xD3] = op(Dcp, IndirectY, 8, false);
+    table[0xD4] = op(Nop, ZeroPageX, 4, false);
+    table[0xD7] = op(Dcp, ZeroPageX, 6, false);
+    table[0xDA] = op(Nop, Implied, 2, false);
+    table[0xDB] = op(Dcp, AbsoluteY, 7, false);
+    table[0xDC] = op(Nop, AbsoluteX, 4, true);
+    table[0xDF] = op(Dcp, AbsoluteX, 7, false);
+    table[0xE2] = op(Nop, Immediate, 2, false);
+    table[0xE3] = op(Isc, IndirectX, 8, false);
+    table[0xE7] = op(Isc, ZeroPage, 5, false);
+    table[0xEB] = op(Sbc, Immediate, 2, false);
+    table[0xEF] = op(Isc, Absolute, 6, false);
+    table[0xF2] = op(Jam, Implied, 2, false);
+    table[0xF3] = op(Isc, IndirectY, 8, false);
+    table[0xF4] = op(Nop, ZeroPageX, 4, false);
+    table[0xF7] = op(Isc, ZeroPageX, 6, false);
+    table[0xFA] = op(Nop, Implied, 2, false);
+    table[0xFB] = op(Isc, AbsoluteY, 7, false);
+    table[0xFC] = op(Nop, AbsoluteX, 4, true);
+    table[0xFF] = op(Isc, AbsoluteX, 7, false);
+
+    // Undocumented and unstable, see UnstableModel
+    table[0x8B] = op(Ane, Immediate, 2, false);
+    table[0x93] = op(Sha, IndirectY, 6, false);
+    table[0x9B] = op(Tas, AbsoluteY, 5, false);
+    table[0x9C] = op(Shy, AbsoluteX, 5, false);
+    table[0x9E] = op(Shx, AbsoluteY, 5, false);
+    table[0x9F] = op(Sha, AbsoluteY, 5, false);
+    table[0xAB] = op(Lxa, Immediate, 2, false);
+    table[0xBB] = op(Las, AbsoluteY, 4, true);
+    table
+};
+
+pub fn lookup(opcode: u8) -> Option<Instruction> {
+    OPCODES[opcode as usize]
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!("{self:?}").to_uppercase();
+        f.pad(&name)
+    }
+}
+
+// Disassembles the instruction at the start of the bytes, which were read
+// from the given address. Returns the text along with the number of bytes
+// it took up, and opcodes without an entry come out as a single data byte
+pub fn disassemble(address: u16, bytes: &[u8]) -> (String, usize) {
+    let Some((&opcode, operand)) = bytes.split_first() else {
+        return (String::new(), 0);
+    };
+
+    let instruction = match lookup(opcode) {
+        Some(instruction) if operand.len() + 1 >= instruction.length as usize => instruction,
+        _ => return (format!(".byte ${opcode:02X}"), 1),
+    };
+
+    let byte = operand.first().copied().unwrap_or(0);
+    let word = u16::from_le_bytes([byte, operand.get(1).copied().unwrap_or(0)]);
+    let mnemonic = instruction.mnemonic;
+
+    let text = match instruction.mode {
+        AddressingMode::Implied => format!("{mnemonic}"),
+        AddressingMode::Accumulator => format!("{mnemonic} A"),
+        AddressingMode::Immediate => format!("{mnemonic} #${byte:02X}"),
+        AddressingMode::ZeroPage => format!("{mnemonic} ${byte:02X}"),
+        AddressingMode::ZeroPageX => format!("{mnemonic} ${byte:02X},X"),
+        AddressingMode::ZeroPageY => format!("{mnemonic} ${byte:02X},Y"),
+        AddressingMode::Absolute => format!("{mnemonic} ${word:04X}"),
+        AddressingMode::AbsoluteX => format!("{mnemonic} ${word:04X},X"),
+        AddressingMode::AbsoluteY => format!("{mnemonic} ${word:04X},Y"),
+        AddressingMode::Indirect => format!("{mnemonic} (${word:04X})"),
+        AddressingMode::IndirectX => format!("{mnemonic} (${byte:02X},X)"),
+        AddressingMode::IndirectY => format!("{mnemonic} (${byte:02X}),Y"),
+        AddressingMode::Relative => {
+            let target = address.wrapping_add(2).wrapping_add(byte as i8 as u16);
+            format!("{mnemonic} ${target:04X}")
+        }
+    };
+
+    (text, instruction.length as usize)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cpu::Cpu6502;
+
+    // Mnemonic, opcode and cycle count of every documented instruction, as
+    // listed in the MOS programming manual
+    const DOCUMENTED: [(&str, u8, u64); 151] = [
+        ("ADC #", 0x69, 2),
+        ("ADC zp", 0x65, 3),
+        ("ADC zp,X", 0x75, 4),
+        ("ADC abs", 0x6D, 4),
+        ("ADC abs,X", 0x7D, 4),
+        ("ADC abs,Y", 0x79, 4),
+        ("ADC (zp,X)", 0x61, 6),
+        ("ADC (zp),Y", 0x71, 5),
+        ("AND #", 0x29, 2),
+        ("AND zp", 0x25, 3),
+        ("AND zp,X", 0x35, 4),
+        ("AND abs", 0x2D, 4),
+        ("AND abs,X", 0x3D, 4),
+        ("AND abs,Y", 0x39, 4),
+        ("AND (zp,X)", 0x21, 6),
+        ("AND (zp),Y", 0x31, 5),
+        ("ASL A", 0x0A, 2),
+        ("ASL zp", 0x06, 5),
+        ("ASL zp,X", 0x16, 6),
+        ("ASL abs", 0x0E, 6),
+        ("ASL abs,X", 0x1E, 7),
+        ("BCC", 0x90, 2),
+        ("BCS", 0xB0, 2),
+        ("BEQ", 0xF0, 2),
+        ("BMI", 0x30, 2),
+        ("BNE", 0xD0, 2),
+        ("BPL", 0x10, 2),
+        ("BVC", 0x50, 2),
+        ("BVS", 0x70, 2),
+        ("BIT zp", 0x24, 3),
+        ("BIT abs", 0x2C, 4),
+        ("BRK", 0x00, 7),
+        ("CLC", 0x18, 2),
+        ("CLD", 0xD8, 2),
+        ("CLI", 0x58, 2),
+        ("CLV", 0xB8, 2),
+        ("CMP #", 0xC9, 2),
+        ("CMP zp", 0xC5, 3),
+        ("CMP zp,X", 0xD5, 4),
+        ("CMP abs", 0xCD, 4),
+        ("CMP abs,X", 0xDD, 4),
+        ("CMP abs,Y", 0xD9, 4),
+        ("CMP (zp,X)", 0xC1, 6),
+        ("CMP (zp),Y", 0xD1, 5),
+        ("CPX #", 0xE0, 2),
+        ("CPX zp", 0xE4, 3),
+        ("CPX abs", 0xEC, 4),
+        ("CPY #", 0xC0, 2),
+        ("CPY zp", 0xC4, 3),
+        ("CPY abs", 0xCC, 4),
+        ("DEC zp", 0xC6, 5),
+        ("DEC zp,X", 0xD6, 6),
+        ("DEC abs", 0xCE, 6),
+        ("DEC abs,X", 0xDE, 7),
+        ("DEX", 0xCA, 2),
+        ("DEY", 0x88, 2),
+        ("EOR #", 0x49, 2),
+        ("EOR zp", 0x45, 3),
+        ("EOR zp,X", 0x55, 4),
+        ("EOR abs", 0x4D, 4),
+        ("EOR abs,X", 0x5D, 4),
+        ("EOR abs,Y", 0x59, 4),
+        ("EOR (zp,X)", 0x41, 6),
+        ("EOR (zp),Y", 0x51, 5),
+        ("INC zp", 0xE6, 5),
+        ("INC zp,X", 0xF6, 6),
+        ("INC abs", 0xEE, 6),
+        ("INC abs,X", 0xFE, 7),
+        ("INX", 0xE8, 2),
+        ("INY", 0xC8, 2),
+        ("JMP abs", 0x4C, 3),
+        ("JMP (abs)", 0x6C, 5),
+        ("JSR", 0x20, 6),
+        ("LDA #", 0xA9, 2),
+        ("LDA zp", 0xA5, 3),
+        ("LDA zp,X", 0xB5, 4),
+        ("LDA abs", 0xAD, 4),
+        ("LDA abs,X", 0xBD, 4),
+        ("LDA abs,Y", 0xB9, 4),
+        ("LDA (zp,X)", 0xA1, 6),
+        ("LDA (zp),Y", 0xB1, 5),
+        ("LDX #", 0xA2, 2),
+        ("LDX zp", 0xA6, 3),
+        ("LDX zp,Y", 0xB6, 4),
+        ("LDX abs", 0xAE, 4),
+        ("LDX abs,Y", 0xBE, 4),
+        ("LDY #", 0xA0, 2),
+        ("LDY zp", 0xA4, 3),
+        ("LDY zp,X", 0xB4, 4),
+        ("LDY abs", 0xAC, 4),
+        ("LDY abs,X", 0xBC, 4),
+        ("LSR A", 0x4A, 2),
+        ("LSR zp", 0x46, 5),
+        ("LSR zp,X", 0x56, 6),
+        ("LSR abs", 0x4E, 6),
+        ("LSR abs,X", 0x5E, 7),
+        ("NOP", 0xEA, 2),
+        ("ORA #", 0x09, 2),
+        ("ORA zp", 0x05, 3),
+        ("ORA zp,X", 0x15, 4),
+        ("ORA abs", 0x0D, 4),
+        ("ORA abs,X", 0x1D, 4),
+        ("ORA abs,Y", 0x19, 4),
+        ("ORA (zp,X)", 0x01, 6),
+        ("ORA (zp),Y", 0x11, 5),
+        ("PHA", 0x48, 3),
+        ("PHP", 0x08, 3),
+        ("PLA", 0x68, 4),
+        ("PLP", 0x28, 4),
+        ("ROL A", 0x2A, 2),
+        ("ROL zp", 0x26, 5),
+        ("ROL zp,X", 0x36, 6),
+        ("ROL abs", 0x2E, 6),
+        ("ROL abs,X", 0x3E, 7),
+        ("ROR A", 0x6A, 2),
+        ("ROR zp", 0x66, 5),
+        ("ROR zp,X", 0x76, 6),
+        ("ROR abs", 0x6E, 6),
+        ("ROR abs,X", 0x7E, 7),
+        ("RTI", 0x40, 6),
+        ("RTS", 0x60, 6),
+        ("SBC #", 0xE9, 2),
+        ("SBC zp", 0xE5, 3),
+        ("SBC zp,X", 0xF5, 4),
+        ("SBC abs", 0xED, 4),
+        ("SBC abs,X", 0xFD, 4),
+        ("SBC abs,Y", 0xF9, 4),
+        ("SBC (zp,X)", 0xE1, 6),
+        ("SBC (zp),Y", 0xF1, 5),
+        ("SEC", 0x38, 2),
+        ("SED", 0xF8, 2),
+        ("SEI", 0x78, 2),
+        ("STA zp", 0x85, 3),
+        ("STA zp,X", 0x95, 4),
+        ("STA abs", 0x8D, 4),
+        ("STA abs,X", 0x9D, 5),
+        ("STA abs,Y", 0x99, 5),
+        ("STA (zp,X)", 0x81, 6),
+        ("STA (zp),Y", 0x91, 6),
+        ("STX zp", 0x86, 3),
+        ("STX zp,Y", 0x96, 4),
+        ("STX abs", 0x8E, 4),
+        ("STY zp", 0x84, 3),
+        ("STY zp,X", 0x94, 4),
+        ("STY abs", 0x8C, 4),
+        ("TAX", 0xAA, 2),
+        ("TAY", 0xA8, 2),
+        ("TSX", 0xBA, 2),
+        ("TXA", 0x8A, 2),
+        ("TXS", 0x9A, 2),
+        ("TYA", 0x98, 2),
+    ];
+
+    // The undocumented opcodes, with the cycle counts given in "No More
+    // Secrets". JAM locks up rather than taking a number of cycles, and
+    // counts as two here for each time it is run over again
+    const UNDOCUMENTED: [(&str, u8, u64); 105] = [
+        ("SLO zp", 0x07, 5),
+        ("SLO zp,X", 0x17, 6),
+        ("SLO abs", 0x0F, 6),
+        ("SLO abs,X", 0x1F, 7),
+        ("SLO abs,Y", 0x1B, 7),
+        ("SLO (zp,X)", 0x03, 8),
+        ("SLO (zp),Y", 0x13, 8),
+        ("RLA zp", 0x27, 5),
+        ("RLA zp,X", 0x37, 6),
+        ("RLA abs", 0x2F, 6),
+        ("RLA abs,X", 0x3F, 7),
+        ("RLA abs,Y", 0x3B, 7),
+        ("RLA (zp,X)", 0x23, 8),
+        ("RLA (zp),Y", 0x33, 8),
+        ("SRE zp", 0x47, 5),
+        ("SRE zp,X", 0x57, 6),
+        ("SRE abs", 0x4F, 6),
+        ("SRE abs,X", 0x5F, 7),
+        ("SRE abs,Y", 0x5B, 7),
+        ("SRE (zp,X)", 0x43, 8),
+        ("SRE (zp),Y", 0x53, 8),
+        ("RRA zp", 0x67, 5),
+        ("RRA zp,X", 0x77, 6),
+        ("RRA abs", 0x6F, 6),
+        ("RRA abs,X", 0x7F, 7),
+        ("RRA abs,Y", 0x7B, 7),
+        ("RRA (zp,X)", 0x63, 8),
+        ("RRA (zp),Y", 0x73, 8),
+        ("SAX zp", 0x87, 3),
+        ("SAX zp,Y", 0x97, 4),
+        ("SAX abs", 0x8F, 4),
+        ("SAX (zp,X)", 0x83, 6),
+        ("LAX zp", 0xA7, 3),
+        ("LAX zp,Y", 0xB7, 4),
+        ("LAX abs", 0xAF, 4),
+        ("LAX abs,Y", 0xBF, 4),
+        ("LAX (zp,X)", 0xA3, 6),
+        ("LAX (zp),Y", 0xB3, 5),
+        ("DCP zp", 0xC7, 5),
+        ("DCP zp,X", 0xD7, 6),
+        ("DCP abs", 0xCF, 6),
+        ("DCP abs,X", 0xDF, 7),
+        ("DCP abs,Y", 0xDB, 7),
+        ("DCP (zp,X)", 0xC3, 8),
+        ("DCP (zp),Y", 0xD3, 8),
+        ("ISC zp", 0xE7, 5),
+        ("ISC zp,X", 0xF7, 6),
+        ("ISC abs", 0xEF, 6),
+        ("ISC abs,X", 0xFF, 7),
+        ("ISC abs,Y", 0xFB, 7),
+        ("ISC (zp,X)", 0xE3, 8),
+        ("ISC (zp),Y", 0xF3, 8),
+        ("ANC #", 0x0B, 2),
+        ("ANC #", 0x2B, 2),
+        ("ALR #", 0x4B, 2),
+        ("ARR #", 0x6B, 2),
+        ("SBX #", 0xCB, 2),
+        ("SBC #", 0xEB, 2),
+        ("NOP", 0x1A, 2),
+        ("NOP", 0x3A, 2),
+        ("NOP", 0x5A, 2),
+        ("NOP", 0x7A, 2),
+        ("NOP", 0xDA, 2),
+        ("NOP", 0xFA, 2),
+        ("NOP #", 0x80, 2),
+        ("NOP #", 0x82, 2),
+        ("NOP #", 0x89, 2),
+        ("NOP #", 0xC2, 2),
+        ("NOP #", 0xE2, 2),
+        ("NOP zp", 0x04, 3),
+        ("NOP zp", 0x44, 3),
+        ("NOP zp", 0x64, 3),
+        ("NOP zp,X", 0x14, 4),
+        ("NOP zp,X", 0x34, 4),
+        ("NOP zp,X", 0x54, 4),
+        ("NOP zp,X", 0x74, 4),
+        ("NOP zp,X", 0xD4, 4),
+        ("NOP zp,X", 0xF4, 4),
+        ("NOP abs", 0x0C, 4),
+        ("NOP abs,X", 0x1C, 4),
+        ("NOP abs,X", 0x3C, 4),
+        ("NOP abs,X", 0x5C, 4),
+        ("NOP abs,X", 0x7C, 4),
+        ("NOP abs,X", 0xDC, 4),
+        ("NOP abs,X", 0xFC, 4),
+        ("JAM", 0x02, 2),
+        ("JAM", 0x12, 2),
+        ("JAM", 0x22, 2),
+        ("JAM", 0x32, 2),
+        ("JAM", 0x42, 2),
+        ("JAM", 0x52, 2),
+        ("JAM", 0x62, 2),
+        ("JAM", 0x72, 2),
+        ("JAM", 0x92, 2),
+        ("JAM", 0xB2, 2),
+        ("JAM", 0xD2, 2),
+        ("JAM", 0xF2, 2),
+        ("ANE #", 0x8B, 2),
+        ("LXA #", 0xAB, 2),
+        ("SHA abs,Y", 0x9F, 5),
+        ("SHA (zp),Y", 0x93, 6),
+        ("SHX abs,Y", 0x9E, 5),
+        ("SHY abs,X", 0x9C, 5),
+        ("TAS abs,Y", 0x9B, 5),
+        ("LAS abs,Y", 0xBB, 4),
+    ];
+
+    // Undocumented reads that take an extra cycle when the index crosses a page
+    const UNDOCUMENTED_PAGE_PENALTY: [u8; 9] =
+        [0x1C, 0x3C, 0x5C, 0x7C, 0xB3, 0xBB, 0xBF, 0xDC, 0xFC];
+
+    // Indexed reads that take an extra cycle when the index crosses a page
+    const PAGE_PENALTY: [u8; 23] = [
+        0x7D, 0x79, 0x71, 0x3D, 0x39, 0x31, 0xDD, 0xD9, 0xD1, 0x5D, 0x59, 0x51, 0xBD, 0xB9, 0xB1,
+        0xBE, 0xBC, 0x1D, 0x19, 0x11, 0xFD, 0xF9, 0xF1,
+    ];
+
+    fn is_branch(opcode: u8) -> bool {
+        opcode & 0x1F == 0x10
+    }
+
+    // Runs one instruction with its operand bytes and zero page pointer
+    // set to $80 and returns the number of cycles it took
+    fn cycles_for(opcode: u8, index: u8) -> u64 {
+        let mut cpu = Cpu6502::with_program(vec![opcode, 0x80, 0x80, 0xFF]);
+        cpu.traps.legacy_halt = false;
+        cpu.bus[0x0080] = 0x80;
+        cpu.bus[0x0081] = 0x80;
+        cpu.x = index;
+        cpu.y = index;
+
+        // bit 5 of a branch opcode is the flag value it branches on, so
+        // set the flags the other way to keep it from being taken
+        if is_branch(opcode) {
+            let flag = opcode & 0x20 == 0;
+            cpu.flags.negative = flag;
+            cpu.flags.overflow = flag;
+            cpu.flags.carry = flag;
+            cpu.flags.zero = flag;
+        }
+
+        let before = cpu.cycles;
+        let opcode = cpu.fetch_byte();
+        cpu.decode(opcode);
+        cpu.cycles - before
+    }
+
+    #[test]
+    fn documented_opcodes() {
+        for (mnemonic, opcode, cycles) in DOCUMENTED {
+            assert_eq!(cycles_for(opcode, 0x00), cycles, "{mnemonic}");
+        }
+    }
+
+    #[test]
+    fn page_crossing_penalty() {
+        for (mnemonic, opcode, cycles) in DOCUMENTED {
+            if is_branch(opcode) {
+                continue;
+            }
+
+            let expected = cycles + PAGE_PENALTY.contains(&opcode) as u64;
+            assert_eq!(cycles_for(opcode, 0x80), expected, "{mnemonic}");
+        }
+    }
+
+    #[test]
+    fn branch_penalties() {
+        for (mnemonic, opcode, cycles) in DOCUMENTED {
+            if !is_branch(opcode) {
+                continue;
+            }
+
+            // a taken branch from $8002 to $8012 stays on the same page,
+            // while one back to $7F82 costs another cycle
+            for (offset, target, penalty) in [(0x10, 0x8012, 1), (0x80, 0x7F82, 2)] {
+                let mut cpu = Cpu6502::with_program(vec![opcode, offset, 0xFF]);
+                let flag = opcode & 0x20 != 0;
+                cpu.flags.negative = flag;
+                cpu.flags.overflow = flag;
+                cpu.flags.carry = flag;
+                cpu.flags.zero = flag;
+
+                let opcode = cpu.fetch_byte();
+                cpu.decode(opcode);
+                assert_eq!(cpu.ip, target, "{mnemonic}");
+                assert_eq!(cpu.cycles, cycles + penalty, "{mnemonic}");
+            }
+        }
+    }
+
+    #[test]
+    fn running_total() {
+        let program: Vec<u8> = vec![
+            0xA2, 0x03, // LDX #$03     2
+            0xCA, //       DEX          2 * 3
+            0xD0, 0xFD, // BNE -3       3 * 2 + 2
+            0xFF,
+        ];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.cycles, 2 + 6 + 8);
+    }
+
+    #[test]
+    fn interrupts_take_seven_cycles() {
+        let mut cpu = Cpu6502::with_program(vec![0xFF]);
+        cpu.bus[0xFFFE] = 0x00;
+        cpu.bus[0xFFFF] = 0x80;
+
+        cpu.irq(true);
+        cpu.run().unwrap();
+        assert_eq!(cpu.cycles, 7);
+    }
+
+    #[test]
+    fn undocumented_opcodes() {
+        for (mnemonic, opcode, cycles) in UNDOCUMENTED {
+            assert_eq!(cycles_for(opcode, 0x00), cycles, "{mnemonic}");
+
+            let expected = cycles + UNDOCUMENTED_PAGE_PENALTY.contains(&opcode) as u64;
+            assert_eq!(cycles_for(opcode, 0x80), expected, "{mnemonic}");
+        }
+    }
+
+    #[test]
+    fn table_covers_every_opcode() {
+        let listed = DOCUMENTED.iter().chain(UNDOCUMENTED.iter());
+        let mut opcodes: Vec<u8> = listed.clone().map(|&(_, opcode, _)| opcode).collect();
+        opcodes.sort();
+        assert_eq!(opcodes, (0..=0xFF).collect::<Vec<u8>>());
+
+        for &(mnemonic, opcode, cycles) in listed {
+            let instruction = lookup(opcode).unwrap();
+            assert_eq!(instruction.cycles as u64, cycles, "{mnemonic}");
+            assert_eq!(instruction.length, instruction.mode.length(), "{mnemonic}");
+            assert_eq!(
+                instruction.page_penalty,
+                PAGE_PENALTY.contains(&opcode) || UNDOCUMENTED_PAGE_PENALTY.contains(&opcode),
+                "{mnemonic}"
+            );
+            assert!(
+                mnemonic.starts_with(&instruction.mnemonic.to_string()),
+                "{mnemonic}"
+            );
+        }
+    }
+
+    #[test]
+    fn disassembly() {
+        let cases: [(&[u8], &str, usize); 17] = [
+            (&[0xEA], "NOP", 1),
+            (&[0x0A], "ASL A", 1),
+            (&[0xA9, 0x69], "LDA #$69", 2),
+            (&[0xA5, 0x42], "LDA $42", 2),
+            (&[0xB5, 0x42], "LDA $42,X", 2),
+            (&[0xB6, 0x42], "LDX $42,Y", 2),
+            (&[0xAD, 0x34, 0x12], "LDA $1234", 3),
+            (&[0xBD, 0x34, 0x12], "LDA $1234,X", 3),
+            (&[0xB9, 0x34, 0x12], "LDA $1234,Y", 3),
+            (&[0x6C, 0xFF, 0x02], "JMP ($02FF)", 3),
+            (&[0xA1, 0x42], "LDA ($42,X)", 2),
+            (&[0xB1, 0x42], "LDA ($42),Y", 2),
+            (&[0xD0, 0xFE], "BNE $8000", 2),
+            (&[0x02, 0xEA], "JAM", 1),
+            (&[0xB3, 0x42], "LAX ($42),Y", 2),
+            (&[0x1C, 0x34, 0x12], "NOP $1234,X", 3),
+            (&[0xEB, 0x69], "SBC #$69", 2),
+        ];
+
+        for (bytes, text, length) in cases {
+            assert_eq!(disassemble(0x8000, bytes), (text.to_string(), length));
+        }
+
+        // an instruction cut short by the end of the bytes is left as data
+        assert_eq!(
+            disassemble(0x8000, &[0xAD, 0x34]),
+            (".byte $AD".to_string(), 1)
+        );
+        assert_eq!(disassemble(0x8000, &[]), (String::new(), 0));
+    }
+}
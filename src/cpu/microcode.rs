@@ -0,0 +1,737 @@
+use crate::bus::Bus;
+use crate::cpu::execute::{operation, stores_high_and, Operation};
+use crate::cpu::{AddressingMode, Cpu6502, Mnemonic, IRQ_VECTOR, NMI_VECTOR, RESET_VECTOR};
+use crate::tasks::{Register, Task};
+
+// The bus access a task makes during its cycle
+pub(super) enum Access {
+    Read(u16),
+    Write(u16, u8),
+}
+
+impl<B: Bus> Cpu6502<B> {
+    // Runs a single clock cycle, making exactly the bus access the 6502
+    // makes on that cycle, dummy reads and writes included. Interrupts are
+    // recognised when a tick starts a new instruction
+    pub fn tick(&mut self) {
+        let Some(task) = self.next_task() else {
+            return;
+        };
+
+        let data = match self.access(task) {
+            Access::Read(addr) => self.bus.read(addr),
+            Access::Write(addr, value) => {
+                self.bus.write(addr, value);
+                self.check_exit_port(addr, value);
+                value
+            }
+        };
+
+        self.complete(task, data);
+    }
+
+    // Whether the last tick finished an instruction, so the next one
+    // fetches an opcode or starts an interrupt
+    pub fn at_instruction_boundary(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    // The task for the coming cycle, starting the next instruction or
+    // interrupt once the last one is done. Nothing runs once halted
+    pub(super) fn next_task(&mut self) -> Option<Task> {
+        if self.halted {
+            return None;
+        }
+
+        if self.tasks.is_empty() {
+            self.start_instruction();
+        }
+
+        self.tasks.pop_front()
+    }
+
+    fn start_instruction(&mut self) {
+        if self.jammed {
+            self.opcode_address = Some(self.ip);
+            self.tasks.push_back(Task::FetchOpcode);
+        } else if self.nmi_pending {
+            self.nmi_pending = false;
+            self.queue_interrupt(NMI_VECTOR);
+        } else if self.irq_line && !self.flags.interrupt_disable {
+            self.queue_interrupt(IRQ_VECTOR);
+        } else {
+            self.opcode_address = Some(self.ip);
+            self.tasks.push_back(Task::FetchOpcode);
+        }
+    }
+
+    // An interrupt replaces the opcode fetch with a read that is thrown
+    // away, then runs like BRK without stepping past a padding byte
+    fn queue_interrupt(&mut self, vector: u16) {
+        use Task::*;
+
+        self.tasks.extend([
+            ReadPc,
+            ReadPc,
+            Push(Register::PcHigh),
+            Push(Register::PcLow),
+            PushInterruptStatus,
+            ReadVectorLow(vector),
+            ReadVectorHigh(vector),
+        ]);
+    }
+
+    // The reset sequence runs like an interrupt with its pushes turned into
+    // reads, so the stack pointer still drops by three. Everything else
+    // reset clears is cleared up front, as a pending NMI, trap or error
+    // doesn't survive RES either
+    pub(super) fn queue_reset(&mut self) {
+        use Task::*;
+
+        self.halted = false;
+        self.jammed = false;
+        self.trap = None;
+        self.exit_code = None;
+        self.error = None;
+        self.nmi_pending = false;
+        self.tasks.clear();
+        self.opcode_address = None;
+        self.pin_cycle = None;
+        self.tasks.extend([
+            ReadPc,
+            ReadPc,
+            SuppressedPush,
+            SuppressedPush,
+            SuppressedPush,
+            ReadVectorLow(RESET_VECTOR),
+            ReadVectorHigh(RESET_VECTOR),
+        ]);
+    }
+
+    fn queue_instruction(&mut self, opcode: u8) {
+        use AddressingMode::*;
+        use Task::*;
+
+        if self.trap_opcode(opcode) {
+            return;
+        }
+
+        let address = self.opcode_address.unwrap_or(self.ip.wrapping_sub(1));
+        let instruction = self.instruction(opcode);
+        if self.refuse_opcode(opcode, address, instruction) {
+            return;
+        }
+
+        let Some(instruction) = instruction else {
+            return;
+        };
+        self.mnemonic = instruction.mnemonic;
+
+        let tasks: &[Task] = match instruction.mnemonic {
+            Mnemonic::Brk => &[
+                SkipByte,
+                Push(Register::PcHigh),
+                Push(Register::PcLow),
+                Push(Register::Status),
+                ReadVectorLow(IRQ_VECTOR),
+                ReadVectorHigh(IRQ_VECTOR),
+            ],
+            Mnemonic::Jsr => &[
+                FetchLow,
+                ReadStack,
+                Push(Register::PcHigh),
+                Push(Register::PcLow),
+                JumpHigh,
+            ],
+            Mnemonic::Rts => &[
+                ReadPc,
+                ReadStack,
+                Pull(Register::PcLow),
+                Pull(Register::PcHigh),
+                IncrementPc,
+            ],
+            Mnemonic::Rti => &[
+                ReadPc,
+                ReadStack,
+                Pull(Register::Status),
+                Pull(Register::PcLow),
+                Pull(Register::PcHigh),
+            ],
+            Mnemonic::Jmp if instruction.mode == Absolute => &[FetchLow, JumpHigh],
+            Mnemonic::Jmp => &[FetchLow, FetchHigh, ReadTargetLow, JumpIndirect],
+            Mnemonic::Pha => &[ReadPc, Push(Register::A)],
+            Mnemonic::Php => &[ReadPc, Push(Register::Status)],
+            Mnemonic::Pla => &[ReadPc, ReadStack, Pull(Register::A)],
+            Mnemonic::Plp => &[ReadPc, ReadStack, Pull(Register::Status)],
+            Mnemonic::Jam => {
+                self.lock_up();
+                &[ReadPc]
+            }
+            _ => &[],
+        };
+
+        if !tasks.is_empty() {
+            self.tasks.extend(tasks);
+            return;
+        }
+
+        let (x, y) = (self.x, self.y);
+        let addressing: &[Task] = match instruction.mode {
+            Implied | Accumulator => &[ExecuteImplied],
+            Immediate => &[FetchImmediate],
+            Relative => &[FetchBranchOffset],
+            ZeroPage => &[FetchZeroPage],
+            ZeroPageX => &[FetchZeroPage, IndexZeroPage(x)],
+            ZeroPageY => &[FetchZeroPage, IndexZeroPage(y)],
+            Absolute => &[FetchLow, FetchHigh],
+            AbsoluteX => &[FetchLow, FetchHighIndexed(x)],
+            AbsoluteY => &[FetchLow, FetchHighIndexed(y)],
+            IndirectX => &[
+                FetchZeroPage,
+                IndexZeroPage(x),
+                ReadTargetLow,
+                ReadTargetHigh,
+            ],
+            IndirectY => &[FetchZeroPage, ReadTargetLow, ReadTargetHighIndexed(y)],
+            Indirect => unreachable!("only JMP is indirect"),
+        };
+        self.tasks.extend(addressing);
+
+        if matches!(
+            instruction.mode,
+            Implied | Accumulator | Immediate | Relative
+        ) {
+            return;
+        }
+
+        // Indexing that can carry into the high byte always spends a cycle
+        // on the unfixed address, except for reads that didn't carry
+        let indexed = matches!(instruction.mode, AbsoluteX | AbsoluteY | IndirectY);
+        let operand: &[Task] = match (operation(instruction.mnemonic), indexed) {
+            (Operation::Read, false) => &[MemoryRead],
+            (Operation::Read, true) => &[ReadIndexed],
+            (Operation::Write, false) => &[MemoryWrite],
+            (Operation::Write, true) => &[FixHigh, MemoryWrite],
+            (Operation::Modify, false) => &[ReadModify, WriteBack, WriteResult],
+            (Operation::Modify, true) => &[FixHigh, ReadModify, WriteBack, WriteResult],
+        };
+        self.tasks.extend(operand);
+    }
+
+    pub(super) fn access(&self, task: Task) -> Access {
+        use Task::*;
+
+        let stack = 0x0100 | self.sp as u16;
+
+        match task {
+            FetchOpcode | ReadPc | SkipByte | ExecuteImplied | FetchImmediate | FetchZeroPage
+            | FetchLow | FetchHigh | FetchHighIndexed(_) | JumpHigh | FetchBranchOffset
+            | BranchTaken | BranchFixHigh | IncrementPc => Access::Read(self.ip),
+
+            IndexZeroPage(_) | ReadTargetLow | ReadIndexed | FixHigh | MemoryRead | ReadModify => {
+                Access::Read(self.pointer)
+            }
+
+            // The pointer's high byte never changes when reading the second
+            // half of the target, which is both zero page wrapping and the
+            // JMP ($xxFF) bug
+            ReadTargetHigh | ReadTargetHighIndexed(_) | JumpIndirect => {
+                let [low, high] = self.pointer.to_le_bytes();
+                Access::Read(u16::from_le_bytes([low.wrapping_add(1), high]))
+            }
+
+            WriteBack | WriteResult => Access::Write(self.pointer, self.data),
+            MemoryWrite => Access::Write(self.pointer, self.stored()),
+
+            ReadStack | SuppressedPush => Access::Read(stack),
+            Push(register) => Access::Write(stack, self.pushed(register)),
+            PushInterruptStatus => Access::Write(stack, self.flags.pushed(false)),
+            Pull(_) => Access::Read(0x0100 | self.sp.wrapping_add(1) as u16),
+
+            ReadVectorLow(vector) => Access::Read(vector),
+            ReadVectorHigh(vector) => Access::Read(vector.wrapping_add(1)),
+        }
+    }
+
+    // Finishes the cycle with the byte that was on the data bus
+    pub(super) fn complete(&mut self, task: Task, data: u8) {
+        use Task::*;
+
+        self.cycles += 1;
+
+        match task {
+            FetchOpcode => {
+                self.ip = self.ip.wrapping_add(1);
+                self.queue_instruction(data);
+            }
+            ReadPc | ReadStack | WriteResult | MemoryWrite => {}
+            SkipByte | IncrementPc => self.ip = self.ip.wrapping_add(1),
+            ExecuteImplied => self.execute_implied(),
+            FetchImmediate => {
+                self.ip = self.ip.wrapping_add(1);
+                self.execute_read(data);
+            }
+            FetchZeroPage => {
+                self.ip = self.ip.wrapping_add(1);
+                self.pointer = data as u16;
+            }
+            FetchLow => {
+                self.ip = self.ip.wrapping_add(1);
+                self.set_pointer_low(data);
+            }
+            FetchHigh => {
+                self.ip = self.ip.wrapping_add(1);
+                self.set_pointer_high(data);
+            }
+            FetchHighIndexed(index) => {
+                self.ip = self.ip.wrapping_add(1);
+                self.set_pointer_high(data);
+                self.index_low(index);
+            }
+            JumpHigh => {
+                self.set_pointer_high(data);
+                self.ip = self.pointer;
+            }
+            IndexZeroPage(index) => {
+                self.pointer = (self.pointer as u8).wrapping_add(index) as u16;
+            }
+            ReadTargetLow | ReadModify => self.data = data,
+            ReadTargetHigh => self.pointer = u16::from_le_bytes([self.data, data]),
+            ReadTargetHighIndexed(index) => {
+                self.pointer = u16::from_le_bytes([self.data, data]);
+                self.index_low(index);
+            }
+            JumpIndirect => self.ip = u16::from_le_bytes([self.data, data]),
+            ReadIndexed => {
+                if self.page_crossed {
+                    self.fix_high();
+                    self.tasks.push_front(MemoryRead);
+                } else {
+                    self.execute_read(data);
+                }
+            }
+            FixHigh => {
+                if stores_high_and(self.mnemonic) {
+                    let [_, high] = self.pointer.to_le_bytes();
+                    self.store_high_and(high, self.page_crossed);
+                    self.page_crossed = false;
+                } else if self.page_crossed {
+                    self.fix_high();
+                }
+            }
+            MemoryRead => self.execute_read(data),
+            WriteBack => self.data = self.execute_modify(self.data),
+            FetchBranchOffset => {
+                self.ip = self.ip.wrapping_add(1);
+                self.data = data;
+
+                if self.branch_condition() {
+                    self.tasks.push_front(BranchTaken);
+                }
+            }
+            BranchTaken => {
+                // The low byte is added first, and fixing up the high byte
+                // takes another cycle when the target is on another page
+                let target = self.ip.wrapping_add(self.data as i8 as u16);
+                self.pointer = target;
+                self.ip = (self.ip & 0xFF00) | (target & 0x00FF);
+
+                if self.ip != target {
+                    self.tasks.push_front(BranchFixHigh);
+                }
+            }
+            BranchFixHigh => self.ip = self.pointer,
+            Push(_) | PushInterruptStatus | SuppressedPush => self.sp = self.sp.wrapping_sub(1),
+            Pull(register) => {
+                self.sp = self.sp.wrapping_add(1);
+                let [low, high] = self.ip.to_le_bytes();
+
+                match register {
+                    Register::A => {
+                        self.a = data;
+                        self.flags.update_zero_and_negative(self.a);
+                    }
+                    Register::Status => self.flags.pull(data),
+                    Register::PcLow => self.ip = u16::from_le_bytes([data, high]),
+                    Register::PcHigh => self.ip = u16::from_le_bytes([low, data]),
+                }
+            }
+            ReadVectorLow(_) => {
+                self.data = data;
+                self.flags.set_interrupt_disable();
+            }
+            ReadVectorHigh(_) => self.ip = u16::from_le_bytes([self.data, data]),
+        }
+
+        if self.tasks.is_empty() {
+            self.trap_exit();
+
+            if let Some(address) = self.opcode_address.take() {
+                self.check_jump_to_self(address);
+            }
+        }
+    }
+
+    // Adds the index to the low byte of the pointer, remembering whether it
+    // carried so a later cycle can fix up the high byte
+    fn index_low(&mut self, index: u8) {
+        let [low, high] = self.pointer.to_le_bytes();
+        let (low, carried) = low.overflowing_add(index);
+        self.pointer = u16::from_le_bytes([low, high]);
+        self.page_crossed = carried;
+    }
+
+    fn fix_high(&mut self) {
+        self.pointer = self.pointer.wrapping_add(0x0100);
+        self.page_crossed = false;
+    }
+
+    fn pushed(&self, register: Register) -> u8 {
+        let [low, high] = self.ip.to_le_bytes();
+
+        match register {
+            Register::A => self.a,
+            Register::Status => self.flags.pushed(true),
+            Register::PcLow => low,
+            Register::PcHigh => high,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bus::Memory;
+
+    // Sets up the same machine twice over, with the operand bytes, index
+    // registers and every flag given, and memory filled with a pattern so
+    // that pointers and stacked values all differ
+    fn machine(opcode: u8, operand: u8, index: u8, flags: bool) -> Cpu6502 {
+        let mut cpu = Cpu6502::new();
+        for addr in 0..=0xFFFF_u16 {
+            let [low, high] = addr.to_le_bytes();
+            cpu.bus[addr as usize] = low.wrapping_mul(13) ^ high;
+        }
+        cpu.load_program(vec![opcode, operand, operand]);
+
+        cpu.x = index;
+        cpu.y = index;
+        cpu.a = 0x42;
+        cpu.flags.carry = flags;
+        cpu.flags.zero = flags;
+        cpu.flags.interrupt_disable = flags;
+        cpu.flags.decimal = flags;
+        cpu.flags.overflow = flags;
+        cpu.flags.negative = flags;
+        cpu
+    }
+
+    // Ticks until the instruction started by the first tick is finished and
+    // returns how many cycles that took
+    fn tick_instruction<B: Bus>(cpu: &mut Cpu6502<B>) -> u64 {
+        let before = cpu.cycles;
+        cpu.tick();
+        while !cpu.at_instruction_boundary() {
+            cpu.tick();
+        }
+        cpu.cycles - before
+    }
+
+    #[test]
+    fn ticks_match_whole_instructions() {
+        // leaving out $FF, which is the legacy halt by default
+        for opcode in 0..0xFF {
+            for (operand, index, flags) in [
+                (0x10, 0x00, false),
+                (0x10, 0x00, true),
+                (0x80, 0x80, false),
+                (0x80, 0x80, true),
+                (0xF0, 0x20, false),
+                (0xFF, 0x01, false),
+            ] {
+                let mut stepped = machine(opcode, operand, index, flags);
+                let before = stepped.cycles;
+                let opcode = stepped.fetch_byte();
+                stepped.decode(opcode);
+                let expected = stepped.cycles - before;
+
+                let mut ticked = machine(opcode, operand, index, flags);
+                let cycles = tick_instruction(&mut ticked);
+
+                let context = format!("opcode ${opcode:02X} operand ${operand:02X}");
+                assert_eq!(cycles, expected, "{context}");
+                assert_eq!(ticked.a, stepped.a, "{context}");
+                assert_eq!(ticked.x, stepped.x, "{context}");
+                assert_eq!(ticked.y, stepped.y, "{context}");
+                assert_eq!(ticked.sp, stepped.sp, "{context}");
+                assert_eq!(ticked.ip, stepped.ip, "{context}");
+                assert_eq!(ticked.flags, stepped.flags, "{context}");
+                assert!(ticked.bus == stepped.bus, "{context}");
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Cycle {
+        Read(u16),
+        Write(u16, u8),
+    }
+
+    // Plain memory that records the access made on every cycle
+    #[derive(Default)]
+    struct TraceBus {
+        memory: Memory,
+        cycles: Vec<Cycle>,
+    }
+
+    impl Bus for TraceBus {
+        fn read(&mut self, addr: u16) -> u8 {
+            self.cycles.push(Cycle::Read(addr));
+            self.memory.read(addr)
+        }
+
+        fn write(&mut self, addr: u16, value: u8) {
+            self.cycles.push(Cycle::Write(addr, value));
+            self.memory.write(addr, value);
+        }
+    }
+
+    fn traced(program: &[u8]) -> Cpu6502<TraceBus> {
+        let mut cpu: Cpu6502<TraceBus> = Cpu6502::default();
+        cpu.load_program(program.to_vec());
+        cpu.bus.cycles.clear();
+        cpu
+    }
+
+    #[test]
+    fn indexed_read_crossing_page() {
+        use Cycle::*;
+
+        // LDA $42FF,X
+        let mut cpu = traced(&[0xBD, 0xFF, 0x42]);
+        cpu.bus.memory[0x4300] = 0x69;
+        cpu.x = 0x01;
+
+        assert_eq!(tick_instruction(&mut cpu), 5);
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(
+            cpu.bus.cycles,
+            vec![
+                Read(0x8000),
+                Read(0x8001),
+                Read(0x8002),
+                Read(0x4200),
+                Read(0x4300)
+            ]
+        );
+    }
+
+    #[test]
+    fn indexed_write_always_reads_first() {
+        use Cycle::*;
+
+        // STA $4200,X
+        let mut cpu = traced(&[0x9D, 0x00, 0x42]);
+        cpu.a = 0x69;
+        cpu.x = 0x01;
+
+        assert_eq!(tick_instruction(&mut cpu), 5);
+        assert_eq!(
+            cpu.bus.cycles,
+            vec![
+                Read(0x8000),
+                Read(0x8001),
+                Read(0x8002),
+                Read(0x4201),
+                Write(0x4201, 0x69)
+            ]
+        );
+    }
+
+    #[test]
+    fn read_modify_write_zeropage_x() {
+        use Cycle::*;
+
+        // INC $42,X
+        let mut cpu = traced(&[0xF6, 0x42]);
+        cpu.bus.memory[0x0043] = 0x68;
+        cpu.x = 0x01;
+
+        assert_eq!(tick_instruction(&mut cpu), 6);
+        assert_eq!(
+            cpu.bus.cycles,
+            vec![
+                Read(0x8000),
+                Read(0x8001),
+                Read(0x0042),
+                Read(0x0043),
+                Write(0x0043, 0x68),
+                Write(0x0043, 0x69)
+            ]
+        );
+    }
+
+    #[test]
+    fn indirect_y_crossing_page() {
+        use Cycle::*;
+
+        // LDA ($FF),Y with the pointer wrapping to $0000 for its high byte
+        let mut cpu = traced(&[0xB1, 0xFF]);
+        cpu.bus.memory[0x00FF] = 0xFF;
+        cpu.bus.memory[0x0000] = 0x42;
+        cpu.bus.memory[0x4300] = 0x69;
+        cpu.y = 0x01;
+
+        assert_eq!(tick_instruction(&mut cpu), 6);
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(
+            cpu.bus.cycles,
+            vec![
+                Read(0x8000),
+                Read(0x8001),
+                Read(0x00FF),
+                Read(0x0000),
+                Read(0x4200),
+                Read(0x4300)
+            ]
+        );
+    }
+
+    #[test]
+    fn subroutine_call_and_return() {
+        use Cycle::*;
+
+        // JSR $9000, then RTS
+        let mut cpu = traced(&[0x20, 0x00, 0x90]);
+        cpu.bus.memory[0x9000] = 0x60;
+
+        assert_eq!(tick_instruction(&mut cpu), 6);
+        assert_eq!(cpu.ip, 0x9000);
+        assert_eq!(tick_instruction(&mut cpu), 6);
+        assert_eq!(cpu.ip, 0x8003);
+        assert_eq!(
+            cpu.bus.cycles,
+            vec![
+                Read(0x8000),
+                Read(0x8001),
+                Read(0x01FD),
+                Write(0x01FD, 0x80),
+                Write(0x01FC, 0x02),
+                Read(0x8002),
+                Read(0x9000),
+                Read(0x9001),
+                Read(0x01FB),
+                Read(0x01FC),
+                Read(0x01FD),
+                Read(0x8002)
+            ]
+        );
+    }
+
+    #[test]
+    fn push_and_pull() {
+        use Cycle::*;
+
+        // PHA, PLA
+        let mut cpu = traced(&[0x48, 0x68]);
+        cpu.a = 0x69;
+
+        assert_eq!(tick_instruction(&mut cpu), 3);
+        cpu.a = 0x00;
+        assert_eq!(tick_instruction(&mut cpu), 4);
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(
+            cpu.bus.cycles,
+            vec![
+                Read(0x8000),
+                Read(0x8001),
+                Write(0x01FD, 0x69),
+                Read(0x8001),
+                Read(0x8002),
+                Read(0x01FC),
+                Read(0x01FD)
+            ]
+        );
+    }
+
+    #[test]
+    fn branch_taken_across_page() {
+        use Cycle::*;
+
+        // BNE +$10 from $80FD lands on $810F, first reading from $800F
+        let mut program = vec![0xEA; 0x100];
+        program[0xFD] = 0xD0;
+        program[0xFE] = 0x10;
+        let mut cpu = traced(&program);
+        cpu.ip = 0x80FD;
+
+        assert_eq!(tick_instruction(&mut cpu), 4);
+        assert_eq!(cpu.ip, 0x810F);
+        assert_eq!(
+            cpu.bus.cycles,
+            vec![Read(0x80FD), Read(0x80FE), Read(0x80FF), Read(0x800F)]
+        );
+    }
+
+    #[test]
+    fn break_and_interrupt() {
+        use Cycle::*;
+
+        let mut cpu = traced(&[0x00, 0xEA, 0xEA]);
+        cpu.bus.memory[0xFFFE] = 0x00;
+        cpu.bus.memory[0xFFFF] = 0x90;
+
+        assert_eq!(tick_instruction(&mut cpu), 7);
+        assert_eq!(cpu.ip, 0x9000);
+        assert!(cpu.flags.interrupt_disable);
+        assert_eq!(
+            cpu.bus.cycles,
+            vec![
+                Read(0x8000),
+                Read(0x8001),
+                Write(0x01FD, 0x80),
+                Write(0x01FC, 0x02),
+                Write(0x01FB, 0b0011_0000),
+                Read(0xFFFE),
+                Read(0xFFFF)
+            ]
+        );
+
+        // a hardware interrupt reads the next opcode without running it,
+        // and pushes the status with the break bit clear
+        let mut cpu = traced(&[0xEA]);
+        cpu.bus.memory[0xFFFA] = 0x00;
+        cpu.bus.memory[0xFFFB] = 0x90;
+        cpu.nmi(true);
+
+        assert_eq!(tick_instruction(&mut cpu), 7);
+        assert_eq!(cpu.ip, 0x9000);
+        assert_eq!(
+            cpu.bus.cycles,
+            vec![
+                Read(0x8000),
+                Read(0x8000),
+                Write(0x01FD, 0x80),
+                Write(0x01FC, 0x00),
+                Write(0x01FB, 0b0010_0000),
+                Read(0xFFFA),
+                Read(0xFFFB)
+            ]
+        );
+    }
+
+    #[test]
+    fn run_finishes_a_ticked_instruction() {
+        // LDA $4269, STA $0200, halt
+        let mut cpu = Cpu6502::with_program(vec![0xAD, 0x69, 0x42, 0x8D, 0x00, 0x02, 0xFF]);
+        cpu.bus[0x4269] = 0x69;
+
+        cpu.tick();
+        cpu.tick();
+        assert!(!cpu.at_instruction_boundary());
+
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x0200], 0x69);
+        assert_eq!(cpu.cycles, 8);
+    }
+}
@@ -0,0 +1,317 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn add<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    if cpu.flags.decimal {
+        add_decimal(cpu, value);
+    } else {
+        add_binary(cpu, value);
+    }
+}
+
+pub(super) fn add_binary<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    let sum = cpu.a as u16 + value as u16 + cpu.flags.carry as u16;
+    let result = sum as u8;
+
+    cpu.flags.carry = sum > 0xFF;
+    cpu.flags.overflow = (cpu.a ^ result) & (value ^ result) & 0x80 != 0;
+    cpu.flags.update_zero_and_negative(result);
+    cpu.a = result;
+}
+
+fn add_decimal<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    let carry = cpu.flags.carry as u8;
+
+    // The NMOS 6502 sets the zero flag from the binary sum, even in decimal mode
+    let binary = cpu.a.wrapping_add(value).wrapping_add(carry);
+
+    let mut low = (cpu.a & 0x0F) + (value & 0x0F) + carry;
+    if low > 0x09 {
+        low = ((low + 0x06) & 0x0F) + 0x10;
+    }
+
+    // Negative and overflow are taken from the sum before the high
+    // nibble is adjusted, treating the high nibbles as signed
+    let signed = (cpu.a & 0xF0) as i8 as i16 + (value & 0xF0) as i8 as i16 + low as i16;
+
+    let mut sum = (cpu.a & 0xF0) as u16 + (value & 0xF0) as u16 + low as u16;
+    if sum > 0x9F {
+        sum += 0x60;
+    }
+
+    cpu.flags.carry = sum > 0xFF;
+    cpu.flags.overflow = !(-128..=127).contains(&signed);
+    cpu.flags.zero = binary == 0;
+    cpu.flags.negative = signed & 0x80 != 0;
+    cpu.a = sum as u8;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn add_with_carry_immediate() {
+        let program: Vec<u8> = vec![0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x27;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+        assert!(!cpu.flags.carry);
+
+        cpu.a = 0x27;
+        cpu.flags.carry = true;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x6A);
+        assert!(!cpu.flags.carry);
+    }
+
+    #[test]
+    fn add_with_carry_flags() {
+        let program: Vec<u8> = vec![0x69, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        // 0x50 + 0x50 overflows into the sign bit
+        cpu.a = 0x50;
+        cpu.bus[0x8001] = 0x50;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0xA0);
+        assert!(cpu.flags.overflow);
+        assert!(cpu.flags.negative);
+        assert!(!cpu.flags.carry);
+
+        // 0xFF + 0x01 carries out and wraps to zero
+        cpu.a = 0xFF;
+        cpu.bus[0x8001] = 0x01;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.overflow);
+
+        // 0x80 + 0xFF is negative + negative = positive
+        cpu.a = 0x80;
+        cpu.bus[0x8001] = 0xFF;
+        cpu.flags.carry = false;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x7F);
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.overflow);
+        assert!(!cpu.flags.negative);
+    }
+
+    #[test]
+    fn add_with_carry_binary_exhaustive() {
+        let program: Vec<u8> = vec![0x69, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        for a in 0..=0xFFu8 {
+            for b in 0..=0xFFu8 {
+                for c in [false, true] {
+                    cpu.a = a;
+                    cpu.bus[0x8001] = b;
+                    cpu.flags.carry = c;
+                    cpu.reset();
+                    cpu.run().unwrap();
+
+                    let sum = a as i32 + b as i32 + c as i32;
+                    let signed = a as i8 as i32 + b as i8 as i32 + c as i32;
+                    assert_eq!(cpu.a, sum as u8, "{a:02X} + {b:02X} + {c}");
+                    assert_eq!(cpu.flags.carry, sum > 0xFF);
+                    assert_eq!(cpu.flags.zero, sum as u8 == 0);
+                    assert_eq!(cpu.flags.overflow, !(-128..=127).contains(&signed));
+                    assert_eq!(cpu.flags.negative, sum & 0x80 != 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn add_with_carry_decimal() {
+        let program: Vec<u8> = vec![0x69, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.flags.decimal = true;
+
+        let cases = [
+            (0x00, 0x00, false, 0x00, false),
+            (0x79, 0x00, true, 0x80, false),
+            (0x24, 0x56, false, 0x80, false),
+            (0x93, 0x82, false, 0x75, true),
+            (0x89, 0x76, false, 0x65, true),
+            (0x89, 0x76, true, 0x66, true),
+            (0x80, 0xF0, false, 0xD0, true),
+            (0x80, 0xFA, false, 0xE0, true),
+            (0x2F, 0x4F, false, 0x74, false),
+            (0x6F, 0x00, true, 0x76, false),
+        ];
+
+        for (a, b, c, result, carry) in cases {
+            cpu.a = a;
+            cpu.bus[0x8001] = b;
+            cpu.flags.carry = c;
+            cpu.reset();
+            cpu.run().unwrap();
+            assert_eq!(cpu.a, result, "{a:02X} + {b:02X} + {c}");
+            assert_eq!(cpu.flags.carry, carry, "{a:02X} + {b:02X} + {c}");
+        }
+    }
+
+    #[test]
+    fn add_with_carry_decimal_flags() {
+        let program: Vec<u8> = vec![0x69, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.flags.decimal = true;
+
+        // Known NMOS results from Bruce Clark's "Decimal Mode" tutorial.
+        // Zero comes from the binary sum, so $99 + $01 clears it and
+        // $80 + $80 sets it, while negative and overflow come from the sum
+        // before the high nibble is adjusted
+        let cases = [
+            // a, b, carry in, result, carry, zero, overflow, negative
+            (0x00, 0x00, false, 0x00, false, true, false, false),
+            (0x99, 0x01, false, 0x00, true, false, false, true),
+            (0x79, 0x00, true, 0x80, false, false, true, true),
+            (0x24, 0x56, false, 0x80, false, false, true, true),
+            (0x93, 0x82, false, 0x75, true, false, true, false),
+            (0x89, 0x76, false, 0x65, true, false, false, false),
+            (0x80, 0xF0, false, 0xD0, true, false, true, false),
+            (0x80, 0xFA, false, 0xE0, true, false, false, true),
+            (0x2F, 0x4F, false, 0x74, false, false, false, false),
+            (0x80, 0x80, false, 0x60, true, true, true, false),
+        ];
+
+        for (a, b, c, result, carry, zero, overflow, negative) in cases {
+            cpu.a = a;
+            cpu.bus[0x8001] = b;
+            cpu.flags.carry = c;
+            cpu.reset();
+            cpu.run().unwrap();
+
+            let context = format!("{a:02X} + {b:02X} + {c}");
+            assert_eq!(cpu.a, result, "{context}");
+            assert_eq!(cpu.flags.carry, carry, "{context}");
+            assert_eq!(cpu.flags.zero, zero, "{context}");
+            assert_eq!(cpu.flags.overflow, overflow, "{context}");
+            assert_eq!(cpu.flags.negative, negative, "{context}");
+        }
+    }
+
+    #[test]
+    fn add_with_carry_decimal_exhaustive() {
+        let program: Vec<u8> = vec![0x69, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.flags.decimal = true;
+
+        for a in 0..=0xFFu8 {
+            for b in 0..=0xFFu8 {
+                for c in [false, true] {
+                    cpu.a = a;
+                    cpu.bus[0x8001] = b;
+                    cpu.flags.carry = c;
+                    cpu.reset();
+                    cpu.run().unwrap();
+
+                    // valid BCD operands must produce the decimal sum
+                    let bcd = |n: u8| n >> 4 < 10 && n & 0x0F < 10;
+                    if bcd(a) && bcd(b) {
+                        let dec = |n: u8| (n >> 4) as u32 * 10 + (n & 0x0F) as u32;
+                        let sum = dec(a) + dec(b) + c as u32;
+                        let expected = (((sum % 100 / 10) << 4) | (sum % 10)) as u8;
+                        assert_eq!(cpu.a, expected);
+                        assert_eq!(cpu.flags.carry, sum > 99);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn add_with_carry_zeropage() {
+        let program: Vec<u8> = vec![0x65, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x27;
+        cpu.bus[0x42] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn add_with_carry_zeropage_x() {
+        let program: Vec<u8> = vec![0x75, 0x41, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x27;
+        cpu.x = 0x01;
+        cpu.bus[0x42] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn add_with_carry_absolute() {
+        let program: Vec<u8> = vec![0x6D, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x27;
+        cpu.bus[0x4269] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn add_with_carry_absolute_x() {
+        let program: Vec<u8> = vec![0x7D, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x27;
+        cpu.x = 0x01;
+        cpu.bus[0x4269] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn add_with_carry_absolute_y() {
+        let program: Vec<u8> = vec![0x79, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x27;
+        cpu.y = 0x01;
+        cpu.bus[0x4269] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn add_with_carry_indirect_x() {
+        let program: Vec<u8> = vec![0x61, 0x10, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x27;
+        cpu.x = 0x04;
+        cpu.bus[0x14] = 0x69;
+        cpu.bus[0x15] = 0x42;
+        cpu.bus[0x4269] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn add_with_carry_indirect_y() {
+        let program: Vec<u8> = vec![0x71, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x27;
+        cpu.y = 0x01;
+        cpu.bus[0x0000] = 0x68;
+        cpu.bus[0x0001] = 0x42;
+        cpu.bus[0x4269] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+}
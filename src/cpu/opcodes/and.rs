@@ -0,0 +1,130 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn and<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    cpu.a &= value;
+    cpu.flags.update_zero_and_negative(cpu.a);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn and_immediate() {
+        let program: Vec<u8> = vec![0x29, 0x6F, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xF0;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x60);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+    }
+
+    #[test]
+    fn and_flags() {
+        let program: Vec<u8> = vec![0x29, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x0F;
+        cpu.bus[0x8001] = 0xF0;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.flags.zero);
+
+        cpu.a = 0xFF;
+        cpu.bus[0x8001] = 0x80;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x80);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn and_zeropage() {
+        let program: Vec<u8> = vec![0x25, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xF0;
+        cpu.bus[0x42] = 0x6F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x60);
+    }
+
+    #[test]
+    fn and_zeropage_x() {
+        let program: Vec<u8> = vec![0x35, 0x41, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xF0;
+        cpu.x = 0x01;
+        cpu.bus[0x42] = 0x6F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x60);
+    }
+
+    #[test]
+    fn and_absolute() {
+        let program: Vec<u8> = vec![0x2D, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xF0;
+        cpu.bus[0x4269] = 0x6F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x60);
+    }
+
+    #[test]
+    fn and_absolute_x() {
+        let program: Vec<u8> = vec![0x3D, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xF0;
+        cpu.x = 0x01;
+        cpu.bus[0x4269] = 0x6F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x60);
+    }
+
+    #[test]
+    fn and_absolute_y() {
+        let program: Vec<u8> = vec![0x39, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xF0;
+        cpu.y = 0x01;
+        cpu.bus[0x4269] = 0x6F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x60);
+    }
+
+    #[test]
+    fn and_indirect_x() {
+        let program: Vec<u8> = vec![0x21, 0x10, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xF0;
+        cpu.x = 0x04;
+        cpu.bus[0x14] = 0x69;
+        cpu.bus[0x15] = 0x42;
+        cpu.bus[0x4269] = 0x6F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x60);
+    }
+
+    #[test]
+    fn and_indirect_y() {
+        let program: Vec<u8> = vec![0x31, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xF0;
+        cpu.y = 0x01;
+        cpu.bus[0x0000] = 0x68;
+        cpu.bus[0x0001] = 0x42;
+        cpu.bus[0x4269] = 0x6F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x60);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+// BIT leaves the accumulator alone. Zero comes from the AND result, while
+// overflow and negative are copied straight from bits 6 and 7 of the operand
+pub fn bit_test<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    if cpu.a & value == 0 {
+        cpu.flags.set_zero();
+    } else {
+        cpu.flags.clear_zero();
+    }
+
+    if (value & (1 << 6)) != 0 {
+        cpu.flags.set_overflow();
+    } else {
+        cpu.flags.clear_overflow();
+    }
+
+    if (value & (1 << 7)) != 0 {
+        cpu.flags.set_negative();
+    } else {
+        cpu.flags.clear_negative();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bit_test_zeropage() {
+        let program: Vec<u8> = vec![0x24, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x01;
+        cpu.bus[0x42] = 0xC0;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x01);
+        assert!(cpu.flags.zero);
+        assert!(cpu.flags.overflow);
+        assert!(cpu.flags.negative);
+
+        cpu.a = 0x01;
+        cpu.bus[0x42] = 0x01;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.overflow);
+        assert!(!cpu.flags.negative);
+    }
+
+    #[test]
+    fn bit_test_absolute() {
+        let program: Vec<u8> = vec![0x2C, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xFF;
+        cpu.bus[0x4269] = 0x40;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0xFF);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.overflow);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x00;
+        cpu.bus[0x4269] = 0x80;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.overflow);
+        assert!(cpu.flags.negative);
+    }
+}
@@ -0,0 +1,412 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+// Compares behave like a subtraction that only keeps the flags. Carry is
+// set when no borrow is needed, that is when the register is >= the operand
+pub fn compare<B: Bus>(cpu: &mut Cpu6502<B>, register: u8, value: u8) {
+    let result = register.wrapping_sub(value);
+    cpu.flags.carry = register >= value;
+    cpu.flags.update_zero_and_negative(result);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn compare_a_immediate() {
+        let program: Vec<u8> = vec![0xC9, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_a_zeropage() {
+        let program: Vec<u8> = vec![0xC5, 0x69, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x69] = 0x42;
+        cpu.a = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_a_zeropage_x() {
+        let program: Vec<u8> = vec![0xD5, 0x68, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x01;
+        cpu.bus[0x69] = 0x42;
+        cpu.a = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_a_absolute() {
+        let program: Vec<u8> = vec![0xCD, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x4269] = 0x42;
+        cpu.a = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_a_absolute_x() {
+        let program: Vec<u8> = vec![0xDD, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x01;
+        cpu.bus[0x4269] = 0x42;
+        cpu.a = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_a_absolute_y() {
+        let program: Vec<u8> = vec![0xD9, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.y = 0x01;
+        cpu.bus[0x4269] = 0x42;
+        cpu.a = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_a_indirect_x() {
+        let program: Vec<u8> = vec![0xC1, 0x10, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x04;
+        cpu.bus[0x14] = 0x69;
+        cpu.bus[0x15] = 0x42;
+        cpu.bus[0x4269] = 0x42;
+        cpu.a = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_a_indirect_y() {
+        let program: Vec<u8> = vec![0xD1, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.y = 0x01;
+        cpu.bus[0x0000] = 0x68;
+        cpu.bus[0x0001] = 0x42;
+        cpu.bus[0x4269] = 0x42;
+        cpu.a = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.a = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_a_unsigned() {
+        let program: Vec<u8> = vec![0xC9, 0x01, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        // the comparison is unsigned even though the result sets negative
+        cpu.a = 0xFF;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_x_immediate() {
+        let program: Vec<u8> = vec![0xE0, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.x = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.x = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_x_zeropage() {
+        let program: Vec<u8> = vec![0xE4, 0x69, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x69] = 0x42;
+        cpu.x = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.x = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.x = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_x_absolute() {
+        let program: Vec<u8> = vec![0xEC, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x4269] = 0x42;
+        cpu.x = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.x = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.x = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_y_immediate() {
+        let program: Vec<u8> = vec![0xC0, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.y = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.y = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.y = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_y_zeropage() {
+        let program: Vec<u8> = vec![0xC4, 0x69, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x69] = 0x42;
+        cpu.y = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.y = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.y = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn compare_y_absolute() {
+        let program: Vec<u8> = vec![0xCC, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x4269] = 0x42;
+        cpu.y = 0x42;
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.y = 0x43;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        cpu.y = 0x41;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn decrement_x<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.x = cpu.x.wrapping_sub(1);
+    cpu.flags.update_zero_and_negative(cpu.x);
+}
+
+pub fn decrement_y<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.y = cpu.y.wrapping_sub(1);
+    cpu.flags.update_zero_and_negative(cpu.y);
+}
+
+pub fn decrement<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) -> u8 {
+    let result = value.wrapping_sub(1);
+    cpu.flags.update_zero_and_negative(result);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decrement_zeropage() {
+        let program: Vec<u8> = vec![0xC6, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x42] = 0x01;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x42], 0x00);
+        assert!(cpu.flags.zero);
+
+        cpu.bus[0x42] = 0x00;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x42], 0xFF);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn decrement_zeropage_x() {
+        let program: Vec<u8> = vec![0xD6, 0x41, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x01;
+        cpu.bus[0x42] = 0x00;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x42], 0xFF);
+    }
+
+    #[test]
+    fn decrement_absolute() {
+        let program: Vec<u8> = vec![0xCE, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x4269] = 0x00;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0xFF);
+    }
+
+    #[test]
+    fn decrement_absolute_x() {
+        let program: Vec<u8> = vec![0xDE, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x01;
+        cpu.bus[0x4269] = 0x00;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0xFF);
+    }
+
+    #[test]
+    fn decrement_x() {
+        let program: Vec<u8> = vec![0xCA, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x01;
+        cpu.run().unwrap();
+        assert_eq!(cpu.x, 0x00);
+        assert!(cpu.flags.zero);
+
+        cpu.x = 0x00;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.x, 0xFF);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn decrement_y() {
+        let program: Vec<u8> = vec![0x88, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.y = 0x01;
+        cpu.run().unwrap();
+        assert_eq!(cpu.y, 0x00);
+        assert!(cpu.flags.zero);
+
+        cpu.y = 0x00;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.y, 0xFF);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn exclusive_or<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    cpu.a ^= value;
+    cpu.flags.update_zero_and_negative(cpu.a);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn exclusive_or_immediate() {
+        let program: Vec<u8> = vec![0x49, 0x96, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xFF;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+    }
+
+    #[test]
+    fn exclusive_or_flags() {
+        let program: Vec<u8> = vec![0x49, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x69;
+        cpu.bus[0x8001] = 0x69;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.flags.zero);
+
+        cpu.a = 0x7F;
+        cpu.bus[0x8001] = 0xFF;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x80);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn exclusive_or_zeropage() {
+        let program: Vec<u8> = vec![0x45, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xFF;
+        cpu.bus[0x42] = 0x96;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn exclusive_or_zeropage_x() {
+        let program: Vec<u8> = vec![0x55, 0x41, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xFF;
+        cpu.x = 0x01;
+        cpu.bus[0x42] = 0x96;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn exclusive_or_absolute() {
+        let program: Vec<u8> = vec![0x4D, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xFF;
+        cpu.bus[0x4269] = 0x96;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn exclusive_or_absolute_x() {
+        let program: Vec<u8> = vec![0x5D, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xFF;
+        cpu.x = 0x01;
+        cpu.bus[0x4269] = 0x96;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn exclusive_or_absolute_y() {
+        let program: Vec<u8> = vec![0x59, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xFF;
+        cpu.y = 0x01;
+        cpu.bus[0x4269] = 0x96;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn exclusive_or_indirect_x() {
+        let program: Vec<u8> = vec![0x41, 0x10, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xFF;
+        cpu.x = 0x04;
+        cpu.bus[0x14] = 0x69;
+        cpu.bus[0x15] = 0x42;
+        cpu.bus[0x4269] = 0x96;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn exclusive_or_indirect_y() {
+        let program: Vec<u8> = vec![0x51, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xFF;
+        cpu.y = 0x01;
+        cpu.bus[0x0000] = 0x68;
+        cpu.bus[0x0001] = 0x42;
+        cpu.bus[0x4269] = 0x96;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+}
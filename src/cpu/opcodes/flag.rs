@@ -0,0 +1,98 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn clear_carry<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.flags.clear_carry();
+}
+
+pub fn set_carry<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.flags.set_carry();
+}
+
+pub fn clear_interrupt_disable<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.flags.clear_interrupt_disable();
+}
+
+pub fn set_interrupt_disable<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.flags.set_interrupt_disable();
+}
+
+pub fn clear_decimal<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.flags.clear_decimal();
+}
+
+pub fn set_decimal<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.flags.set_decimal();
+}
+
+// There is no SEV, overflow can only be set by ADC, SBC, BIT or the SO pin
+pub fn clear_overflow<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.flags.clear_overflow();
+}
+
+pub fn no_operation<B: Bus>(_cpu: &mut Cpu6502<B>) {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn carry() {
+        let program: Vec<u8> = vec![0x38, 0xFF, 0x18, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.run().unwrap();
+        assert!(cpu.flags.carry);
+
+        cpu.halted = false;
+        cpu.run().unwrap();
+        assert!(!cpu.flags.carry);
+    }
+
+    #[test]
+    fn interrupt_disable() {
+        let program: Vec<u8> = vec![0x78, 0xFF, 0x58, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.run().unwrap();
+        assert!(cpu.flags.interrupt_disable);
+
+        cpu.halted = false;
+        cpu.run().unwrap();
+        assert!(!cpu.flags.interrupt_disable);
+    }
+
+    #[test]
+    fn decimal() {
+        let program: Vec<u8> = vec![0xF8, 0xFF, 0xD8, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.run().unwrap();
+        assert!(cpu.flags.decimal);
+
+        cpu.halted = false;
+        cpu.run().unwrap();
+        assert!(!cpu.flags.decimal);
+    }
+
+    #[test]
+    fn clear_overflow() {
+        let program: Vec<u8> = vec![0xB8, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.flags.overflow = true;
+        cpu.run().unwrap();
+        assert!(!cpu.flags.overflow);
+    }
+
+    #[test]
+    fn no_operation() {
+        let program: Vec<u8> = vec![0xEA, 0xEA, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.ip, 0x8003);
+        assert_eq!(cpu.a, 0x00);
+        assert_eq!(cpu.sp, 0xFD);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn increment_x<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.x = cpu.x.wrapping_add(1);
+    cpu.flags.update_zero_and_negative(cpu.x);
+}
+
+pub fn increment_y<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.y = cpu.y.wrapping_add(1);
+    cpu.flags.update_zero_and_negative(cpu.y);
+}
+
+pub fn increment<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) -> u8 {
+    let result = value.wrapping_add(1);
+    cpu.flags.update_zero_and_negative(result);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn increment_zeropage() {
+        let program: Vec<u8> = vec![0xE6, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x42] = 0xFF;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x42], 0x00);
+        assert!(cpu.flags.zero);
+
+        cpu.bus[0x42] = 0x7F;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x42], 0x80);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn increment_zeropage_x() {
+        let program: Vec<u8> = vec![0xF6, 0x41, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x01;
+        cpu.bus[0x42] = 0x7F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x42], 0x80);
+    }
+
+    #[test]
+    fn increment_absolute() {
+        let program: Vec<u8> = vec![0xEE, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x4269] = 0x7F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x80);
+    }
+
+    #[test]
+    fn increment_absolute_x() {
+        let program: Vec<u8> = vec![0xFE, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x01;
+        cpu.bus[0x4269] = 0x7F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x80);
+    }
+
+    #[test]
+    fn increment_x() {
+        let program: Vec<u8> = vec![0xE8, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0xFF;
+        cpu.run().unwrap();
+        assert_eq!(cpu.x, 0x00);
+        assert!(cpu.flags.zero);
+
+        cpu.x = 0x7F;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.x, 0x80);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn increment_y() {
+        let program: Vec<u8> = vec![0xC8, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.y = 0xFF;
+        cpu.run().unwrap();
+        assert_eq!(cpu.y, 0x00);
+        assert!(cpu.flags.zero);
+
+        cpu.y = 0x7F;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.y, 0x80);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+}
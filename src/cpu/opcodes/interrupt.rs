@@ -0,0 +1,67 @@
+use crate::bus::Bus;
+use crate::cpu::{Cpu6502, IRQ_VECTOR};
+
+pub fn force_break<B: Bus>(cpu: &mut Cpu6502<B>) {
+    // BRK skips a padding byte, so the return address is BRK + 2
+    cpu.fetch_byte();
+    cpu.interrupt(IRQ_VECTOR, true);
+}
+
+pub fn return_from_interrupt<B: Bus>(cpu: &mut Cpu6502<B>) {
+    let status = cpu.pull();
+    cpu.flags.pull(status);
+
+    // Unlike RTS, the pulled address is the exact address to resume at
+    let low = cpu.pull();
+    let high = cpu.pull();
+    cpu.set_pointer_high(high);
+    cpu.set_pointer_low(low);
+    cpu.ip = cpu.pointer;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn force_break() {
+        let program: Vec<u8> = vec![0x00, 0x42, 0xFF, 0xA9, 0x69, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0xFFFE] = 0x03;
+        cpu.bus[0xFFFF] = 0x80;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+        assert!(cpu.flags.interrupt_disable);
+
+        // return address, then status with break and unused bits set
+        assert_eq!(cpu.sp, 0xFA);
+        assert_eq!(cpu.bus[0x01FD], 0x80);
+        assert_eq!(cpu.bus[0x01FC], 0x02);
+        assert_eq!(cpu.bus[0x01FB], 0b0011_0001);
+    }
+
+    #[test]
+    fn return_from_interrupt() {
+        let program: Vec<u8> = vec![
+            0x00, 0x00, // BRK
+            0xA2, 0x42, // LDX #$42
+            0xFF, //
+            0xA9, 0x69, // LDA #$69
+            0x40, // RTI
+        ];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0xFFFE] = 0x05;
+        cpu.bus[0xFFFF] = 0x80;
+        cpu.flags.overflow = true;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.x, 0x42);
+        assert_eq!(cpu.sp, 0xFD);
+        assert!(cpu.flags.overflow);
+        assert!(!cpu.flags.interrupt_disable);
+        assert!(!cpu.flags.break_command);
+    }
+}
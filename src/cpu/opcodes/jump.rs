@@ -0,0 +1,95 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn jump_subroutine<B: Bus>(cpu: &mut Cpu6502<B>) {
+    let low = cpu.fetch_byte();
+    let high = cpu.fetch_byte();
+
+    // JSR pushes the address of its own last byte rather than the
+    // address of the next instruction
+    let [ret_low, ret_high] = cpu.ip.wrapping_sub(1).to_le_bytes();
+    cpu.push(ret_high);
+    cpu.push(ret_low);
+
+    cpu.set_pointer_high(high);
+    cpu.set_pointer_low(low);
+    cpu.ip = cpu.pointer;
+}
+
+pub fn return_from_subroutine<B: Bus>(cpu: &mut Cpu6502<B>) {
+    let low = cpu.pull();
+    let high = cpu.pull();
+    cpu.set_pointer_high(high);
+    cpu.set_pointer_low(low);
+    cpu.ip = cpu.pointer.wrapping_add(1);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn jump_absolute() {
+        let program: Vec<u8> = vec![0x4C, 0x05, 0x80, 0xA9, 0x42, 0xA9, 0x69, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.ip, 0x8008);
+    }
+
+    #[test]
+    fn jump_indirect() {
+        let program: Vec<u8> = vec![0x6C, 0x00, 0x02, 0xA9, 0x42, 0xA9, 0x69, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x0200] = 0x05;
+        cpu.bus[0x0201] = 0x80;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.ip, 0x8008);
+    }
+
+    #[test]
+    fn jump_indirect_page_wrap() {
+        let program: Vec<u8> = vec![0x6C, 0xFF, 0x02, 0xA9, 0x42, 0xA9, 0x69, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x02FF] = 0x05;
+        cpu.bus[0x0200] = 0x80;
+        cpu.bus[0x0300] = 0x00;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.ip, 0x8008);
+    }
+
+    #[test]
+    fn jump_subroutine() {
+        let program: Vec<u8> = vec![0x20, 0x04, 0x80, 0xFF, 0xA9, 0x69, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.sp, 0xFB);
+        assert_eq!(cpu.bus[0x01FD], 0x80);
+        assert_eq!(cpu.bus[0x01FC], 0x02);
+    }
+
+    #[test]
+    fn return_from_subroutine() {
+        let program: Vec<u8> = vec![
+            0x20, 0x06, 0x80, // JSR $8006
+            0xA2, 0x42, // LDX #$42
+            0xFF, //
+            0xA9, 0x69, // LDA #$69
+            0x60, // RTS
+        ];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.x, 0x42);
+        assert_eq!(cpu.sp, 0xFD);
+        assert_eq!(cpu.ip, 0x8006);
+    }
+}
@@ -1,170 +1,9 @@
+use crate::bus::Bus;
 use crate::cpu::Cpu6502;
 
-pub fn load_a_immediate(cpu: &mut Cpu6502) {
-    let byte = cpu.fetch_byte();
-    cpu.a = byte;
-
-    if cpu.a == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.a & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
-}
-
-pub fn load_a_zeropage(cpu: &mut Cpu6502) {
-    let byte = cpu.fetch_byte();
-    cpu.set_pointer_high(0x00);
-    cpu.set_pointer_low(byte);
-    cpu.a = cpu.read_memory();
-
-    if cpu.a == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.a & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
-}
-
-pub fn load_a_zeropage_x(cpu: &mut Cpu6502) {
-    // LDA zeropage, X
-    let byte = cpu.fetch_byte();
-    let byte = byte.wrapping_add(cpu.x);
-    cpu.set_pointer_high(0x00);
-    cpu.set_pointer_low(byte);
-    cpu.a = cpu.read_memory();
-
-    if cpu.a == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.a & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
-}
-
-pub fn load_a_absolute(cpu: &mut Cpu6502) {
-    let low = cpu.fetch_byte();
-    let high = cpu.fetch_byte();
-    cpu.set_pointer_high(high);
-    cpu.set_pointer_low(low);
-    cpu.a = cpu.read_memory();
-
-    if cpu.a == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.a & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
-}
-
-pub fn load_a_absolute_x(cpu: &mut Cpu6502) {
-    // LDA absolute, X
-    let low = cpu.fetch_byte();
-    let high = cpu.fetch_byte();
-    let low = low.wrapping_add(cpu.x);
-    cpu.set_pointer_high(high);
-    cpu.set_pointer_low(low);
-    cpu.a = cpu.read_memory();
-
-    if cpu.a == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.a & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
-}
-
-pub fn load_a_absolute_y(cpu: &mut Cpu6502) {
-    // LDA absolute, Y
-    let low = cpu.fetch_byte();
-    let high = cpu.fetch_byte();
-    let low = low.wrapping_add(cpu.y);
-    cpu.set_pointer_high(high);
-    cpu.set_pointer_low(low);
-    cpu.a = cpu.read_memory();
-
-    if cpu.a == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.a & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
-}
-
-pub fn load_a_indirect_x(cpu: &mut Cpu6502) {
-    // LDA (indirect, X)
-    let zp = cpu.fetch_byte();
-    let zp = zp.wrapping_add(cpu.x);
-    let low = cpu.memory[zp as usize];
-    let high = cpu.memory[(zp + 1) as usize];
-    cpu.set_pointer_high(high);
-    cpu.set_pointer_low(low);
-    cpu.a = cpu.read_memory();
-
-    if cpu.a == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.a & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
-}
-
-pub fn load_a_indirect_y(cpu: &mut Cpu6502) {
-    // LDA (indirect), Y
-    let zp = cpu.fetch_byte();
-    let low = cpu.memory[zp as usize];
-    let high = cpu.memory[(zp + 1) as usize];
-    let low = low.wrapping_add(cpu.y);
-    cpu.set_pointer_high(high);
-    cpu.set_pointer_low(low);
-    cpu.a = cpu.read_memory();
-
-    if cpu.a == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.a & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
+pub fn load_a<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    cpu.a = value;
+    cpu.flags.update_zero_and_negative(value);
 }
 
 #[cfg(test)]
@@ -176,15 +15,15 @@ mod test {
         let program: Vec<u8> = vec![0xA9, 0x69, 0xFF, 0xA9, 0x00, 0xFF, 0xA9, 0xFF, 0xFF];
         let mut cpu = Cpu6502::with_program(program);
 
-        cpu.run();
+        cpu.run().unwrap();
         assert_eq!(cpu.a, 0x69);
 
         cpu.halted = false;
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.zero);
 
         cpu.halted = false;
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.negative);
     }
 
@@ -193,18 +32,18 @@ mod test {
         let program: Vec<u8> = vec![0xA5, 0x69, 0xFF];
         let mut cpu = Cpu6502::with_program(program);
 
-        cpu.memory[0x69] = 0x69;
-        cpu.run();
+        cpu.bus[0x69] = 0x69;
+        cpu.run().unwrap();
         assert_eq!(cpu.a, 0x69);
 
-        cpu.memory[0x69] = 0x00;
+        cpu.bus[0x69] = 0x00;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.zero);
 
-        cpu.memory[0x69] = 0xFF;
+        cpu.bus[0x69] = 0xFF;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.negative);
     }
 
@@ -213,19 +52,19 @@ mod test {
         let program: Vec<u8> = vec![0xB5, 0x00, 0xFF];
         let mut cpu = Cpu6502::with_program(program);
 
-        cpu.memory[0x01] = 0x69;
+        cpu.bus[0x01] = 0x69;
         cpu.x = 0x01;
-        cpu.run();
+        cpu.run().unwrap();
         assert_eq!(cpu.a, 0x69);
 
-        cpu.memory[0x01] = 0x00;
+        cpu.bus[0x01] = 0x00;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.zero);
 
-        cpu.memory[0x01] = 0xFF;
+        cpu.bus[0x01] = 0xFF;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.negative);
     }
 
@@ -234,18 +73,18 @@ mod test {
         let program: Vec<u8> = vec![0xAD, 0x00, 0x00, 0xFF];
         let mut cpu = Cpu6502::with_program(program);
 
-        cpu.memory[0x00] = 0x69;
-        cpu.run();
+        cpu.bus[0x00] = 0x69;
+        cpu.run().unwrap();
         assert_eq!(cpu.a, 0x69);
 
-        cpu.memory[0x00] = 0x00;
+        cpu.bus[0x00] = 0x00;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.zero);
 
-        cpu.memory[0x00] = 0xFF;
+        cpu.bus[0x00] = 0xFF;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.negative);
     }
 
@@ -254,19 +93,19 @@ mod test {
         let program: Vec<u8> = vec![0xBD, 0x68, 0x42, 0xFF];
         let mut cpu = Cpu6502::with_program(program);
 
-        cpu.memory[0x4269] = 0x69;
+        cpu.bus[0x4269] = 0x69;
         cpu.x = 0x01;
-        cpu.run();
+        cpu.run().unwrap();
         assert_eq!(cpu.a, 0x69);
 
-        cpu.memory[0x4269] = 0x00;
+        cpu.bus[0x4269] = 0x00;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.zero);
 
-        cpu.memory[0x4269] = 0xFF;
+        cpu.bus[0x4269] = 0xFF;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.negative);
     }
 
@@ -275,19 +114,19 @@ mod test {
         let program: Vec<u8> = vec![0xB9, 0x68, 0x42, 0xFF];
         let mut cpu = Cpu6502::with_program(program);
 
-        cpu.memory[0x4269] = 0x69;
+        cpu.bus[0x4269] = 0x69;
         cpu.y = 0x01;
-        cpu.run();
+        cpu.run().unwrap();
         assert_eq!(cpu.a, 0x69);
 
-        cpu.memory[0x4269] = 0x00;
+        cpu.bus[0x4269] = 0x00;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.zero);
 
-        cpu.memory[0x4269] = 0xFF;
+        cpu.bus[0x4269] = 0xFF;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.negative);
     }
 
@@ -297,23 +136,23 @@ mod test {
         let mut cpu = Cpu6502::with_program(program);
 
         cpu.x = 0x04;
-        cpu.memory[0x14] = 0x20;
-        cpu.memory[0x20] = 0x69;
-        cpu.run();
+        cpu.bus[0x14] = 0x20;
+        cpu.bus[0x20] = 0x69;
+        cpu.run().unwrap();
         assert_eq!(cpu.a, 0x69);
 
         cpu.x = 0x04;
-        cpu.memory[0x14] = 0x20;
-        cpu.memory[0x20] = 0x00;
+        cpu.bus[0x14] = 0x20;
+        cpu.bus[0x20] = 0x00;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.zero);
 
         cpu.x = 0x04;
-        cpu.memory[0x14] = 0x20;
-        cpu.memory[0x20] = 0xFF;
+        cpu.bus[0x14] = 0x20;
+        cpu.bus[0x20] = 0xFF;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.negative);
     }
 
@@ -323,21 +162,128 @@ mod test {
         let mut cpu = Cpu6502::with_program(program);
 
         cpu.y = 0x01;
-        cpu.memory[0x0000] = 0x41;
-        cpu.memory[0x0001] = 0x42;
+        cpu.bus[0x0000] = 0x41;
+        cpu.bus[0x0001] = 0x42;
 
-        cpu.memory[0x4242] = 0x69;
-        cpu.run();
+        cpu.bus[0x4242] = 0x69;
+        cpu.run().unwrap();
         assert_eq!(cpu.a, 0x69);
 
-        cpu.memory[0x4242] = 0x00;
+        cpu.bus[0x4242] = 0x00;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.zero);
 
-        cpu.memory[0x4242] = 0xFF;
+        cpu.bus[0x4242] = 0xFF;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.negative);
     }
+
+    #[test]
+    fn load_a_zeropage_x_wraps() {
+        // $F0,X with X = $20 stays in zero page and reads $10
+        let mut cpu = Cpu6502::with_program(vec![0xB5, 0xF0, 0xFF]);
+        cpu.bus[0x0010] = 0x69;
+        cpu.bus[0x0110] = 0x42;
+        cpu.x = 0x20;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.cycles, 4);
+    }
+
+    #[test]
+    fn load_a_absolute_x_page_cross() {
+        // $42FF,X with X = 1 carries into the high byte and reads $4300
+        let mut cpu = Cpu6502::with_program(vec![0xBD, 0xFF, 0x42, 0xFF]);
+        cpu.bus[0x4200] = 0x42;
+        cpu.bus[0x4300] = 0x69;
+        cpu.x = 0x01;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.cycles, 5);
+    }
+
+    #[test]
+    fn load_a_absolute_x_wraps() {
+        // $FFFF,X with X = 2 wraps around the address space to $0001
+        let mut cpu = Cpu6502::with_program(vec![0xBD, 0xFF, 0xFF, 0xFF]);
+        cpu.bus[0x0001] = 0x69;
+        cpu.x = 0x02;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.cycles, 5);
+    }
+
+    #[test]
+    fn load_a_absolute_y_page_cross() {
+        let mut cpu = Cpu6502::with_program(vec![0xB9, 0x80, 0x42, 0xFF]);
+        cpu.bus[0x4210] = 0x42;
+        cpu.bus[0x4310] = 0x69;
+        cpu.y = 0x90;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.cycles, 5);
+    }
+
+    #[test]
+    fn load_a_indirect_x_index_wraps() {
+        // ($F0,X) with X = $20 reads its pointer from $10 and $11
+        let mut cpu = Cpu6502::with_program(vec![0xA1, 0xF0, 0xFF]);
+        cpu.bus[0x0010] = 0x69;
+        cpu.bus[0x0011] = 0x42;
+        cpu.bus[0x4269] = 0x69;
+        cpu.x = 0x20;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.cycles, 6);
+    }
+
+    #[test]
+    fn load_a_indirect_x_pointer_wraps() {
+        // a pointer at $FF takes its high byte from $00, not $0100
+        let mut cpu = Cpu6502::with_program(vec![0xA1, 0xFE, 0xFF]);
+        cpu.bus[0x00FF] = 0x69;
+        cpu.bus[0x0000] = 0x42;
+        cpu.bus[0x0100] = 0x43;
+        cpu.bus[0x4269] = 0x69;
+        cpu.x = 0x01;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn load_a_indirect_y_pointer_wraps() {
+        let mut cpu = Cpu6502::with_program(vec![0xB1, 0xFF, 0xFF]);
+        cpu.bus[0x00FF] = 0x68;
+        cpu.bus[0x0000] = 0x42;
+        cpu.bus[0x0100] = 0x43;
+        cpu.bus[0x4269] = 0x69;
+        cpu.y = 0x01;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.cycles, 5);
+    }
+
+    #[test]
+    fn load_a_indirect_y_page_cross() {
+        // ($10),Y pointing at $42FF with Y = 1 reads $4300
+        let mut cpu = Cpu6502::with_program(vec![0xB1, 0x10, 0xFF]);
+        cpu.bus[0x0010] = 0xFF;
+        cpu.bus[0x0011] = 0x42;
+        cpu.bus[0x4200] = 0x42;
+        cpu.bus[0x4300] = 0x69;
+        cpu.y = 0x01;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.cycles, 6);
+    }
 }
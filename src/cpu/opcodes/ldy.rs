@@ -1,100 +1,9 @@
+use crate::bus::Bus;
 use crate::cpu::Cpu6502;
 
-pub fn load_y_immediate(cpu: &mut Cpu6502) {
-    let byte = cpu.fetch_byte();
-    cpu.y = byte;
-
-    if cpu.y == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.y & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
-}
-
-pub fn load_y_zeropage(cpu: &mut Cpu6502) {
-    let byte = cpu.fetch_byte();
-    cpu.set_pointer_high(0x00);
-    cpu.set_pointer_low(byte);
-    cpu.y = cpu.read_memory();
-
-    if cpu.y == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.y & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
-}
-
-pub fn load_y_zeropage_x(cpu: &mut Cpu6502) {
-    let byte = cpu.fetch_byte();
-    let byte = byte.wrapping_add(cpu.x);
-    cpu.set_pointer_high(0x00);
-    cpu.set_pointer_low(byte);
-    cpu.y = cpu.read_memory();
-
-    if cpu.y == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.y & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
-}
-
-pub fn load_y_absolute(cpu: &mut Cpu6502) {
-    let low = cpu.fetch_byte();
-    let high = cpu.fetch_byte();
-    cpu.set_pointer_high(high);
-    cpu.set_pointer_low(low);
-    cpu.y = cpu.read_memory();
-
-    if cpu.y == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.y & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
-}
-
-pub fn load_y_absolute_x(cpu: &mut Cpu6502) {
-    let low = cpu.fetch_byte();
-    let high = cpu.fetch_byte();
-    let low = low.wrapping_add(cpu.x);
-    cpu.set_pointer_high(high);
-    cpu.set_pointer_low(low);
-    cpu.y = cpu.read_memory();
-
-    if cpu.y == 0 {
-        cpu.flags.set_zero();
-    } else {
-        cpu.flags.clear_zero();
-    }
-
-    if (cpu.y & (1 << 7)) != 0 {
-        cpu.flags.set_negative();
-    } else {
-        cpu.flags.clear_negative();
-    }
+pub fn load_y<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    cpu.y = value;
+    cpu.flags.update_zero_and_negative(value);
 }
 
 #[cfg(test)]
@@ -105,7 +14,7 @@ mod test {
     fn load_y_immediate() {
         let program: Vec<u8> = vec![0xA0, 0x69, 0xFF];
         let mut cpu = Cpu6502::with_program(program);
-        cpu.run();
+        cpu.run().unwrap();
 
         assert_eq!(cpu.y, 0x69);
     }
@@ -114,8 +23,8 @@ mod test {
     fn load_y_zeropage() {
         let program: Vec<u8> = vec![0xA4, 0x69, 0xFF];
         let mut cpu = Cpu6502::with_program(program);
-        cpu.memory[0x69] = 0x69;
-        cpu.run();
+        cpu.bus[0x69] = 0x69;
+        cpu.run().unwrap();
 
         assert_eq!(cpu.y, 0x69);
     }
@@ -125,19 +34,19 @@ mod test {
         let program: Vec<u8> = vec![0xB4, 0x00, 0xFF];
         let mut cpu = Cpu6502::with_program(program);
 
-        cpu.memory[0x01] = 0x69;
+        cpu.bus[0x01] = 0x69;
         cpu.x = 0x01;
-        cpu.run();
+        cpu.run().unwrap();
         assert_eq!(cpu.y, 0x69);
 
-        cpu.memory[0x01] = 0x00;
+        cpu.bus[0x01] = 0x00;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.zero);
 
-        cpu.memory[0x01] = 0xFF;
+        cpu.bus[0x01] = 0xFF;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.negative);
     }
 
@@ -145,7 +54,7 @@ mod test {
     fn load_y_absolute() {
         let program: Vec<u8> = vec![0xAC, 0x00, 0x80, 0xFF];
         let mut cpu = Cpu6502::with_program(program);
-        cpu.run();
+        cpu.run().unwrap();
 
         assert_eq!(cpu.y, 0xAC);
     }
@@ -155,19 +64,43 @@ mod test {
         let program: Vec<u8> = vec![0xBC, 0x68, 0x42, 0xFF];
         let mut cpu = Cpu6502::with_program(program);
 
-        cpu.memory[0x4269] = 0x69;
+        cpu.bus[0x4269] = 0x69;
         cpu.x = 0x01;
-        cpu.run();
+        cpu.run().unwrap();
         assert_eq!(cpu.y, 0x69);
 
-        cpu.memory[0x4269] = 0x00;
+        cpu.bus[0x4269] = 0x00;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.zero);
 
-        cpu.memory[0x4269] = 0xFF;
+        cpu.bus[0x4269] = 0xFF;
         cpu.reset();
-        cpu.run();
+        cpu.run().unwrap();
         assert!(cpu.flags.negative);
     }
+
+    #[test]
+    fn load_y_zeropage_x_wraps() {
+        let mut cpu = Cpu6502::with_program(vec![0xB4, 0xFF, 0xFF]);
+        cpu.bus[0x0001] = 0x69;
+        cpu.bus[0x0101] = 0x42;
+        cpu.x = 0x02;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.y, 0x69);
+        assert_eq!(cpu.cycles, 4);
+    }
+
+    #[test]
+    fn load_y_absolute_x_page_cross() {
+        let mut cpu = Cpu6502::with_program(vec![0xBC, 0xFF, 0x42, 0xFF]);
+        cpu.bus[0x4200] = 0x42;
+        cpu.bus[0x4300] = 0x69;
+        cpu.x = 0x01;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.y, 0x69);
+        assert_eq!(cpu.cycles, 5);
+    }
 }
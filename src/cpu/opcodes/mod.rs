@@ -1,7 +1,47 @@
+mod adc;
+mod and;
+mod asl;
+mod bit;
+mod cmp;
+mod dec;
+mod eor;
+mod flag;
+mod inc;
+mod interrupt;
+mod jump;
 mod lda;
 mod ldx;
 mod ldy;
+mod lsr;
+mod ora;
+mod rol;
+mod ror;
+mod sbc;
+mod stack;
+mod transfer;
+mod undocumented;
+mod unstable;
 
+pub use adc::*;
+pub use and::*;
+pub use asl::*;
+pub use bit::*;
+pub use cmp::*;
+pub use dec::*;
+pub use eor::*;
+pub use flag::*;
+pub use inc::*;
+pub use interrupt::*;
+pub use jump::*;
 pub use lda::*;
 pub use ldx::*;
 pub use ldy::*;
+pub use lsr::*;
+pub use ora::*;
+pub use rol::*;
+pub use ror::*;
+pub use sbc::*;
+pub use stack::*;
+pub use transfer::*;
+pub use undocumented::*;
+pub use unstable::*;
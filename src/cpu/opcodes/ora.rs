@@ -0,0 +1,129 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn or<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    cpu.a |= value;
+    cpu.flags.update_zero_and_negative(cpu.a);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn or_immediate() {
+        let program: Vec<u8> = vec![0x09, 0x09, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x60;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+    }
+
+    #[test]
+    fn or_flags() {
+        let program: Vec<u8> = vec![0x09, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x00;
+        cpu.bus[0x8001] = 0x00;
+        cpu.run().unwrap();
+        assert!(cpu.flags.zero);
+
+        cpu.a = 0x01;
+        cpu.bus[0x8001] = 0x80;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x81);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn or_zeropage() {
+        let program: Vec<u8> = vec![0x05, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x60;
+        cpu.bus[0x42] = 0x09;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn or_zeropage_x() {
+        let program: Vec<u8> = vec![0x15, 0x41, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x60;
+        cpu.x = 0x01;
+        cpu.bus[0x42] = 0x09;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn or_absolute() {
+        let program: Vec<u8> = vec![0x0D, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x60;
+        cpu.bus[0x4269] = 0x09;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn or_absolute_x() {
+        let program: Vec<u8> = vec![0x1D, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x60;
+        cpu.x = 0x01;
+        cpu.bus[0x4269] = 0x09;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn or_absolute_y() {
+        let program: Vec<u8> = vec![0x19, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x60;
+        cpu.y = 0x01;
+        cpu.bus[0x4269] = 0x09;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn or_indirect_x() {
+        let program: Vec<u8> = vec![0x01, 0x10, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x60;
+        cpu.x = 0x04;
+        cpu.bus[0x14] = 0x69;
+        cpu.bus[0x15] = 0x42;
+        cpu.bus[0x4269] = 0x09;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn or_indirect_y() {
+        let program: Vec<u8> = vec![0x11, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x60;
+        cpu.y = 0x01;
+        cpu.bus[0x0000] = 0x68;
+        cpu.bus[0x0001] = 0x42;
+        cpu.bus[0x4269] = 0x09;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+}
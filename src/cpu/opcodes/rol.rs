@@ -0,0 +1,104 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn rotate_left_accumulator<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.a = rotate_left(cpu, cpu.a);
+}
+
+pub fn rotate_left<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) -> u8 {
+    let result = (value << 1) | cpu.flags.carry as u8;
+    cpu.flags.carry = value & 0x80 != 0;
+    cpu.flags.update_zero_and_negative(result);
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rotate_left_accumulator() {
+        let program: Vec<u8> = vec![0x2A, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x81;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x03);
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+
+        cpu.a = 0x40;
+        cpu.flags.carry = false;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x80);
+        assert!(!cpu.flags.carry);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn rotate_left_flags() {
+        let program: Vec<u8> = vec![0x2A, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x00;
+        cpu.flags.carry = false;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.negative);
+    }
+
+    #[test]
+    fn rotate_left_zeropage() {
+        let program: Vec<u8> = vec![0x26, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x42] = 0x81;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x42], 0x03);
+        assert!(cpu.flags.carry);
+    }
+
+    #[test]
+    fn rotate_left_zeropage_x() {
+        let program: Vec<u8> = vec![0x36, 0x41, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x01;
+        cpu.bus[0x42] = 0x81;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x42], 0x03);
+        assert!(cpu.flags.carry);
+    }
+
+    #[test]
+    fn rotate_left_absolute() {
+        let program: Vec<u8> = vec![0x2E, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x4269] = 0x40;
+        cpu.flags.carry = false;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x80);
+        assert!(!cpu.flags.carry);
+    }
+
+    #[test]
+    fn rotate_left_absolute_x() {
+        let program: Vec<u8> = vec![0x3E, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x01;
+        cpu.bus[0x4269] = 0x40;
+        cpu.flags.carry = false;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x80);
+        assert!(!cpu.flags.carry);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn rotate_right_accumulator<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.a = rotate_right(cpu, cpu.a);
+}
+
+pub fn rotate_right<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) -> u8 {
+    let result = (value >> 1) | (cpu.flags.carry as u8) << 7;
+    cpu.flags.carry = value & 0x01 != 0;
+    cpu.flags.update_zero_and_negative(result);
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rotate_right_accumulator() {
+        let program: Vec<u8> = vec![0x6A, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x81;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0xC0);
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+
+        cpu.a = 0x02;
+        cpu.flags.carry = false;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x01);
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.negative);
+    }
+
+    #[test]
+    fn rotate_right_flags() {
+        let program: Vec<u8> = vec![0x6A, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x00;
+        cpu.flags.carry = false;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.carry);
+        assert!(!cpu.flags.negative);
+    }
+
+    #[test]
+    fn rotate_right_zeropage() {
+        let program: Vec<u8> = vec![0x66, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x42] = 0x81;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x42], 0xC0);
+        assert!(cpu.flags.carry);
+    }
+
+    #[test]
+    fn rotate_right_zeropage_x() {
+        let program: Vec<u8> = vec![0x76, 0x41, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x01;
+        cpu.bus[0x42] = 0x81;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x42], 0xC0);
+        assert!(cpu.flags.carry);
+    }
+
+    #[test]
+    fn rotate_right_absolute() {
+        let program: Vec<u8> = vec![0x6E, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.bus[0x4269] = 0x02;
+        cpu.flags.carry = false;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x01);
+        assert!(!cpu.flags.carry);
+    }
+
+    #[test]
+    fn rotate_right_absolute_x() {
+        let program: Vec<u8> = vec![0x7E, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.x = 0x01;
+        cpu.bus[0x4269] = 0x02;
+        cpu.flags.carry = false;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x4269], 0x01);
+        assert!(!cpu.flags.carry);
+    }
+}
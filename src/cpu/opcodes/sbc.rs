@@ -0,0 +1,297 @@
+use super::adc::add_binary;
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn subtract<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    if cpu.flags.decimal {
+        subtract_decimal(cpu, value);
+    } else {
+        add_binary(cpu, !value);
+    }
+}
+
+fn subtract_decimal<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    let a = cpu.a;
+    let borrow = !cpu.flags.carry as i16;
+
+    // The NMOS 6502 sets every flag from the binary difference and only
+    // adjusts the accumulator in decimal mode
+    add_binary(cpu, !value);
+
+    let mut low = (a & 0x0F) as i16 - (value & 0x0F) as i16 - borrow;
+    if low < 0 {
+        low = ((low - 0x06) & 0x0F) - 0x10;
+    }
+
+    let mut difference = (a & 0xF0) as i16 - (value & 0xF0) as i16 + low;
+    if difference < 0 {
+        difference -= 0x60;
+    }
+
+    cpu.a = difference as u8;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn subtract_with_carry_immediate() {
+        let program: Vec<u8> = vec![0xE9, 0x27, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0x90;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+        assert!(cpu.flags.carry);
+
+        cpu.a = 0x90;
+        cpu.flags.carry = false;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x68);
+        assert!(cpu.flags.carry);
+    }
+
+    #[test]
+    fn subtract_with_carry_flags() {
+        let program: Vec<u8> = vec![0xE9, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        // 0x00 - 0x01 borrows
+        cpu.a = 0x00;
+        cpu.bus[0x8001] = 0x01;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0xFF);
+        assert!(!cpu.flags.carry);
+        assert!(cpu.flags.negative);
+        assert!(!cpu.flags.overflow);
+
+        // 0x80 - 0x01 is negative - positive = positive
+        cpu.a = 0x80;
+        cpu.bus[0x8001] = 0x01;
+        cpu.flags.carry = true;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x7F);
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.overflow);
+
+        // 0x42 - 0x42 is zero without a borrow
+        cpu.a = 0x42;
+        cpu.bus[0x8001] = 0x42;
+        cpu.flags.carry = true;
+        cpu.reset();
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.flags.zero);
+        assert!(cpu.flags.carry);
+    }
+
+    #[test]
+    fn subtract_with_carry_binary_exhaustive() {
+        let program: Vec<u8> = vec![0xE9, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        for a in 0..=0xFFu8 {
+            for b in 0..=0xFFu8 {
+                for c in [false, true] {
+                    cpu.a = a;
+                    cpu.bus[0x8001] = b;
+                    cpu.flags.carry = c;
+                    cpu.reset();
+                    cpu.run().unwrap();
+
+                    let difference = a as i32 - b as i32 - !c as i32;
+                    let signed = a as i8 as i32 - b as i8 as i32 - !c as i32;
+                    assert_eq!(cpu.a, difference as u8, "{a:02X} - {b:02X} - {}", !c);
+                    assert_eq!(cpu.flags.carry, difference >= 0);
+                    assert_eq!(cpu.flags.zero, difference as u8 == 0);
+                    assert_eq!(cpu.flags.overflow, !(-128..=127).contains(&signed));
+                    assert_eq!(cpu.flags.negative, difference & 0x80 != 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn subtract_with_carry_decimal() {
+        let program: Vec<u8> = vec![0xE9, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.flags.decimal = true;
+
+        let cases = [
+            (0x00, 0x00, false, 0x99, false),
+            (0x00, 0x00, true, 0x00, true),
+            (0x00, 0x01, true, 0x99, false),
+            (0x0A, 0x00, true, 0x0A, true),
+            (0x0B, 0x00, false, 0x0A, true),
+            (0x9A, 0x00, true, 0x9A, true),
+            (0x9B, 0x00, false, 0x9A, true),
+            (0x46, 0x12, true, 0x34, true),
+            (0x40, 0x13, true, 0x27, true),
+            (0x32, 0x02, false, 0x29, true),
+            (0x12, 0x21, true, 0x91, false),
+            (0x21, 0x34, true, 0x87, false),
+        ];
+
+        for (a, b, c, result, carry) in cases {
+            cpu.a = a;
+            cpu.bus[0x8001] = b;
+            cpu.flags.carry = c;
+            cpu.reset();
+            cpu.run().unwrap();
+            assert_eq!(cpu.a, result, "{a:02X} - {b:02X} - {}", !c);
+            assert_eq!(cpu.flags.carry, carry, "{a:02X} - {b:02X} - {}", !c);
+        }
+    }
+
+    #[test]
+    fn subtract_with_carry_decimal_exhaustive() {
+        let program: Vec<u8> = vec![0xE9, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        for a in 0..=0xFFu8 {
+            for b in 0..=0xFFu8 {
+                for c in [false, true] {
+                    cpu.a = a;
+                    cpu.bus[0x8001] = b;
+                    cpu.flags.carry = c;
+                    cpu.flags.decimal = false;
+                    cpu.reset();
+                    cpu.run().unwrap();
+                    let (carry, zero) = (cpu.flags.carry, cpu.flags.zero);
+                    let (overflow, negative) = (cpu.flags.overflow, cpu.flags.negative);
+
+                    cpu.a = a;
+                    cpu.flags.carry = c;
+                    cpu.flags.decimal = true;
+                    cpu.reset();
+                    cpu.run().unwrap();
+
+                    // Bruce Clark's "Decimal Mode" tutorial, sequence 3
+                    let (a, b, c) = (a as i32, b as i32, c as i32);
+                    let mut al = (a & 0x0F) - (b & 0x0F) + c - 1;
+                    if al < 0 {
+                        al = ((al - 0x06) & 0x0F) - 0x10;
+                    }
+                    let mut difference = (a & 0xF0) - (b & 0xF0) + al;
+                    if difference < 0 {
+                        difference -= 0x60;
+                    }
+                    assert_eq!(cpu.a, difference as u8, "{a:02X} - {b:02X} - {}", 1 - c);
+
+                    // every flag matches binary mode on the NMOS 6502
+                    assert_eq!(cpu.flags.carry, carry);
+                    assert_eq!(cpu.flags.zero, zero);
+                    assert_eq!(cpu.flags.overflow, overflow);
+                    assert_eq!(cpu.flags.negative, negative);
+
+                    // valid BCD operands must produce the decimal difference
+                    let bcd = |n: i32| n >> 4 < 10 && n & 0x0F < 10;
+                    if bcd(a) && bcd(b) {
+                        let dec = |n: i32| (n >> 4) * 10 + (n & 0x0F);
+                        let difference = (dec(a) - dec(b) - (1 - c)).rem_euclid(100);
+                        let expected = (((difference / 10) << 4) | (difference % 10)) as u8;
+                        assert_eq!(cpu.a, expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn subtract_with_carry_zeropage() {
+        let program: Vec<u8> = vec![0xE5, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xAB;
+        cpu.flags.carry = true;
+        cpu.bus[0x42] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn subtract_with_carry_zeropage_x() {
+        let program: Vec<u8> = vec![0xF5, 0x41, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xAB;
+        cpu.flags.carry = true;
+        cpu.x = 0x01;
+        cpu.bus[0x42] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn subtract_with_carry_absolute() {
+        let program: Vec<u8> = vec![0xED, 0x69, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xAB;
+        cpu.flags.carry = true;
+        cpu.bus[0x4269] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn subtract_with_carry_absolute_x() {
+        let program: Vec<u8> = vec![0xFD, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xAB;
+        cpu.flags.carry = true;
+        cpu.x = 0x01;
+        cpu.bus[0x4269] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn subtract_with_carry_absolute_y() {
+        let program: Vec<u8> = vec![0xF9, 0x68, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xAB;
+        cpu.flags.carry = true;
+        cpu.y = 0x01;
+        cpu.bus[0x4269] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn subtract_with_carry_indirect_x() {
+        let program: Vec<u8> = vec![0xE1, 0x10, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xAB;
+        cpu.flags.carry = true;
+        cpu.x = 0x04;
+        cpu.bus[0x14] = 0x69;
+        cpu.bus[0x15] = 0x42;
+        cpu.bus[0x4269] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn subtract_with_carry_indirect_y() {
+        let program: Vec<u8> = vec![0xF1, 0x00, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.a = 0xAB;
+        cpu.flags.carry = true;
+        cpu.y = 0x01;
+        cpu.bus[0x0000] = 0x68;
+        cpu.bus[0x0001] = 0x42;
+        cpu.bus[0x4269] = 0x42;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn push_a<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.push(cpu.a);
+}
+
+pub fn pull_a<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.a = cpu.pull();
+    cpu.flags.update_zero_and_negative(cpu.a);
+}
+
+pub fn push_status<B: Bus>(cpu: &mut Cpu6502<B>) {
+    // PHP always pushes with the break and unused bits set
+    cpu.push(cpu.flags.pushed(true));
+}
+
+pub fn pull_status<B: Bus>(cpu: &mut Cpu6502<B>) {
+    let status = cpu.pull();
+    cpu.flags.pull(status);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn push_a() {
+        let program: Vec<u8> = vec![0xA9, 0x69, 0x48, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.sp, 0xFC);
+        assert_eq!(cpu.bus[0x01FD], 0x69);
+    }
+
+    #[test]
+    fn pull_a() {
+        let program: Vec<u8> = vec![0xA9, 0x69, 0x48, 0xA9, 0x00, 0x68, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert_eq!(cpu.sp, 0xFD);
+        assert!(!cpu.flags.zero);
+
+        let program: Vec<u8> = vec![0xA9, 0x00, 0x48, 0xA9, 0xFF, 0x68, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+
+        let program: Vec<u8> = vec![0xA9, 0xFF, 0x48, 0xA9, 0x00, 0x68, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn push_status() {
+        let program: Vec<u8> = vec![0x08, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.flags.carry = true;
+        cpu.flags.negative = true;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x01FD], 0b1011_0001);
+    }
+
+    #[test]
+    fn pull_status() {
+        let program: Vec<u8> = vec![0xA9, 0xFF, 0x48, 0x28, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.zero);
+        assert!(cpu.flags.interrupt_disable);
+        assert!(cpu.flags.decimal);
+        assert!(cpu.flags.overflow);
+        assert!(cpu.flags.negative);
+
+        // the break and unused bits don't exist in the status register
+        assert!(!cpu.flags.break_command);
+        assert!(!cpu.flags.unused);
+    }
+
+    #[test]
+    fn push_and_pull_status() {
+        let program: Vec<u8> = vec![0x08, 0xA9, 0x00, 0x28, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+
+        cpu.flags.overflow = true;
+        cpu.run().unwrap();
+        assert!(cpu.flags.overflow);
+        assert!(!cpu.flags.zero);
+        assert_eq!(cpu.sp, 0xFD);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+pub fn transfer_a_to_x<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.x = cpu.a;
+    cpu.flags.update_zero_and_negative(cpu.x);
+}
+
+pub fn transfer_a_to_y<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.y = cpu.a;
+    cpu.flags.update_zero_and_negative(cpu.y);
+}
+
+pub fn transfer_x_to_a<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.a = cpu.x;
+    cpu.flags.update_zero_and_negative(cpu.a);
+}
+
+pub fn transfer_y_to_a<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.a = cpu.y;
+    cpu.flags.update_zero_and_negative(cpu.a);
+}
+
+pub fn transfer_sp_to_x<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.x = cpu.sp;
+    cpu.flags.update_zero_and_negative(cpu.x);
+}
+
+// TXS is the only transfer that leaves the flags alone
+pub fn transfer_x_to_sp<B: Bus>(cpu: &mut Cpu6502<B>) {
+    cpu.sp = cpu.x;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn transfer_a_to_x() {
+        let program: Vec<u8> = vec![0xA9, 0x69, 0xAA, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+        assert_eq!(cpu.x, 0x69);
+
+        let program: Vec<u8> = vec![0xA9, 0x80, 0xA2, 0x00, 0xAA, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+        assert_eq!(cpu.x, 0x80);
+        assert!(!cpu.flags.zero);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn transfer_a_to_y() {
+        let program: Vec<u8> = vec![0xA9, 0x00, 0xA0, 0x69, 0xA8, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.y, 0x00);
+        assert!(cpu.flags.zero);
+    }
+
+    #[test]
+    fn transfer_x_to_a() {
+        let program: Vec<u8> = vec![0xA2, 0x69, 0x8A, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x69);
+        assert!(!cpu.flags.zero);
+        assert!(!cpu.flags.negative);
+    }
+
+    #[test]
+    fn transfer_y_to_a() {
+        let program: Vec<u8> = vec![0xA0, 0xFF, 0x98, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0xFF);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn transfer_sp_to_x() {
+        let program: Vec<u8> = vec![0xBA, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.x, 0xFD);
+        assert!(cpu.flags.negative);
+
+        cpu.reset();
+        cpu.sp = 0x00;
+        cpu.run().unwrap();
+        assert_eq!(cpu.x, 0x00);
+        assert!(cpu.flags.zero);
+    }
+
+    #[test]
+    fn transfer_x_to_sp() {
+        let program: Vec<u8> = vec![0xA2, 0x00, 0xA9, 0x69, 0x9A, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.sp, 0x00);
+        assert!(!cpu.flags.zero);
+    }
+}
@@ -0,0 +1,432 @@
+use super::adc::add;
+use super::and::and;
+use super::asl::shift_left;
+use super::cmp::compare;
+use super::eor::exclusive_or;
+use super::lsr::{shift_right, shift_right_accumulator};
+use super::ora::or;
+use super::rol::rotate_left;
+use super::ror::rotate_right;
+use super::sbc::subtract;
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+// The stable undocumented instructions. Most of them are two documented
+// instructions run back to back on the same operand, because the opcode
+// decoder enables both at once
+
+// LAX
+pub fn load_a_and_x<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    cpu.a = value;
+    cpu.x = value;
+    cpu.flags.update_zero_and_negative(value);
+}
+
+// SLO: ASL, then ORA with the result
+pub fn shift_left_or<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) -> u8 {
+    let result = shift_left(cpu, value);
+    or(cpu, result);
+    result
+}
+
+// RLA: ROL, then AND with the result
+pub fn rotate_left_and<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) -> u8 {
+    let result = rotate_left(cpu, value);
+    and(cpu, result);
+    result
+}
+
+// SRE: LSR, then EOR with the result
+pub fn shift_right_exclusive_or<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) -> u8 {
+    let result = shift_right(cpu, value);
+    exclusive_or(cpu, result);
+    result
+}
+
+// RRA: ROR, then ADC the result with the carry rotated out
+pub fn rotate_right_add<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) -> u8 {
+    let result = rotate_right(cpu, value);
+    add(cpu, result);
+    result
+}
+
+// DCP: DEC, then CMP with the result
+pub fn decrement_compare<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) -> u8 {
+    let result = value.wrapping_sub(1);
+    compare(cpu, cpu.a, result);
+    result
+}
+
+// ISC: INC, then SBC the result
+pub fn increment_subtract<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) -> u8 {
+    let result = value.wrapping_add(1);
+    subtract(cpu, result);
+    result
+}
+
+// ANC: AND, with the negative flag copied into carry
+pub fn and_carry<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    and(cpu, value);
+    cpu.flags.carry = cpu.flags.negative;
+}
+
+// ALR: AND, then LSR A
+pub fn and_shift_right<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    and(cpu, value);
+    shift_right_accumulator(cpu);
+}
+
+// ARR: AND, then ROR A, except that carry and overflow come from bits 6
+// and 5 of the result as the adder is involved. In decimal mode the
+// result is also adjusted a nibble at a time, like a broken ADC
+pub fn and_rotate_right<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    let anded = cpu.a & value;
+    let mut result = (anded >> 1) | (cpu.flags.carry as u8) << 7;
+
+    cpu.flags.update_zero_and_negative(result);
+    cpu.flags.overflow = (anded ^ result) & 0x40 != 0;
+
+    if cpu.flags.decimal {
+        let (low, high) = (anded & 0x0F, anded >> 4);
+
+        if low + (low & 0x01) > 0x05 {
+            result = (result & 0xF0) | (result.wrapping_add(0x06) & 0x0F);
+        }
+
+        cpu.flags.carry = high + (high & 0x01) > 0x05;
+        if cpu.flags.carry {
+            result = result.wrapping_add(0x60);
+        }
+    } else {
+        cpu.flags.carry = result & 0x40 != 0;
+    }
+
+    cpu.a = result;
+}
+
+// SBX: X = (A & X) - operand, setting the flags like CMP. The borrow in
+// is ignored, as is decimal mode
+pub fn and_x_subtract<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    let anded = cpu.a & cpu.x;
+    compare(cpu, anded, value);
+    cpu.x = anded.wrapping_sub(value);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clock::Throttle;
+    use crate::cpu::StopReason;
+
+    #[test]
+    fn load_a_and_x() {
+        // LAX $42, LAX ($10),Y across a page
+        let program: Vec<u8> = vec![0xA7, 0x42, 0xB3, 0x10, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0x42] = 0x80;
+        cpu.bus[0x10] = 0xFF;
+        cpu.bus[0x11] = 0x42;
+        cpu.bus[0x4300] = 0x69;
+        cpu.y = 0x01;
+
+        cpu.run_for_instructions(1).unwrap();
+        assert_eq!((cpu.a, cpu.x), (0x80, 0x80));
+        assert!(cpu.flags.negative);
+        assert_eq!(cpu.cycles, 3);
+
+        cpu.run().unwrap();
+        assert_eq!((cpu.a, cpu.x), (0x69, 0x69));
+        assert!(!cpu.flags.negative);
+        assert_eq!(cpu.cycles, 3 + 6);
+    }
+
+    #[test]
+    fn store_a_and_x() {
+        // SAX $42, SAX $40,Y
+        let program: Vec<u8> = vec![0x87, 0x42, 0x97, 0x40, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.a = 0xF0;
+        cpu.x = 0x3C;
+        cpu.y = 0x01;
+        cpu.flags.zero = true;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x42], 0x30);
+        assert_eq!(cpu.bus[0x41], 0x30);
+        assert!(cpu.flags.zero);
+        assert_eq!(cpu.cycles, 3 + 4);
+    }
+
+    #[test]
+    fn shift_left_or() {
+        // SLO $4200,X
+        let program: Vec<u8> = vec![0x1F, 0x00, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0x4201] = 0x81;
+        cpu.a = 0x10;
+        cpu.x = 0x01;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x4201], 0x02);
+        assert_eq!(cpu.a, 0x12);
+        assert!(cpu.flags.carry);
+        assert_eq!(cpu.cycles, 7);
+    }
+
+    #[test]
+    fn rotate_left_and() {
+        // RLA $42
+        let program: Vec<u8> = vec![0x27, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0x42] = 0xC0;
+        cpu.a = 0x0F;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x42], 0x81);
+        assert_eq!(cpu.a, 0x01);
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.zero);
+        assert_eq!(cpu.cycles, 5);
+    }
+
+    #[test]
+    fn shift_right_exclusive_or() {
+        // SRE ($40,X)
+        let program: Vec<u8> = vec![0x43, 0x40, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0x42] = 0x00;
+        cpu.bus[0x43] = 0x02;
+        cpu.bus[0x0200] = 0x03;
+        cpu.a = 0x81;
+        cpu.x = 0x02;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x0200], 0x01);
+        assert_eq!(cpu.a, 0x80);
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.negative);
+        assert_eq!(cpu.cycles, 8);
+    }
+
+    #[test]
+    fn rotate_right_add() {
+        // RRA $42: $03 rotates to $01 with carry out, then $10 + $01 + 1
+        let program: Vec<u8> = vec![0x67, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0x42] = 0x03;
+        cpu.a = 0x10;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x42], 0x01);
+        assert_eq!(cpu.a, 0x12);
+        assert!(!cpu.flags.carry);
+        assert_eq!(cpu.cycles, 5);
+    }
+
+    #[test]
+    fn decrement_compare() {
+        // DCP ($10),Y always takes the extra cycle, like any write
+        let program: Vec<u8> = vec![0xD3, 0x10, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0x10] = 0x00;
+        cpu.bus[0x11] = 0x02;
+        cpu.bus[0x0201] = 0x43;
+        cpu.a = 0x42;
+        cpu.y = 0x01;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x0201], 0x42);
+        assert!(cpu.flags.zero);
+        assert!(cpu.flags.carry);
+        assert_eq!(cpu.cycles, 8);
+    }
+
+    #[test]
+    fn increment_subtract() {
+        // ISC $4200,Y
+        let program: Vec<u8> = vec![0xFB, 0x00, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0x4200] = 0x0F;
+        cpu.a = 0x20;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x4200], 0x10);
+        assert_eq!(cpu.a, 0x10);
+        assert!(cpu.flags.carry);
+        assert_eq!(cpu.cycles, 7);
+    }
+
+    #[test]
+    fn and_carry() {
+        // ANC #$80, ANC #$01
+        let program: Vec<u8> = vec![0x0B, 0x80, 0x2B, 0x01, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.a = 0xFF;
+
+        cpu.run_for_instructions(1).unwrap();
+        assert_eq!(cpu.a, 0x80);
+        assert!(cpu.flags.carry);
+
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.flags.zero);
+        assert!(!cpu.flags.carry);
+    }
+
+    #[test]
+    fn and_shift_right() {
+        // ALR #$03
+        let program: Vec<u8> = vec![0x4B, 0x03, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.a = 0xFF;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x01);
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.negative);
+        assert_eq!(cpu.cycles, 2);
+    }
+
+    #[test]
+    fn and_rotate_right() {
+        // ARR #$FF with carry in: $C0 becomes $E0, with carry from bit 6
+        // and overflow from bit 6 xor bit 5
+        let program: Vec<u8> = vec![0x6B, 0xFF, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.a = 0xC0;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0xE0);
+        assert!(cpu.flags.carry);
+        assert!(!cpu.flags.overflow);
+        assert!(cpu.flags.negative);
+
+        // $40 becomes $20: bit 6 clears carry, bits 6 and 5 differ
+        cpu.reset();
+        cpu.a = 0x40;
+        cpu.flags.carry = false;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x20);
+        assert!(!cpu.flags.carry);
+        assert!(cpu.flags.overflow);
+    }
+
+    #[test]
+    fn and_rotate_right_decimal() {
+        // $99 rotates to $4C, then both nibbles are adjusted
+        let program: Vec<u8> = vec![0x6B, 0xFF, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.a = 0x99;
+        cpu.flags.decimal = true;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0xA2);
+        assert!(cpu.flags.carry);
+        assert!(cpu.flags.overflow);
+        assert!(!cpu.flags.negative);
+    }
+
+    #[test]
+    fn and_x_subtract() {
+        // SBX #$10 ignores the carry and decimal flags
+        let program: Vec<u8> = vec![0xCB, 0x10, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.a = 0xF3;
+        cpu.x = 0x3F;
+        cpu.flags.decimal = true;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.x, 0x23);
+        assert_eq!(cpu.a, 0xF3);
+        assert!(cpu.flags.carry);
+
+        cpu.reset();
+        cpu.x = 0x0F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.x, 0xF3);
+        assert!(!cpu.flags.carry);
+        assert!(cpu.flags.negative);
+    }
+
+    #[test]
+    fn undocumented_subtract() {
+        // $EB is a copy of SBC #
+        let program: Vec<u8> = vec![0xEB, 0x01, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.a = 0x10;
+        cpu.flags.carry = true;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.a, 0x0F);
+        assert_eq!(cpu.cycles, 2);
+    }
+
+    #[test]
+    fn no_operations() {
+        // NOP, NOP #, NOP zp, NOP zp,X, NOP abs, NOP abs,X across a page
+        let program: Vec<u8> = vec![
+            0x1A, 0x80, 0x69, 0x04, 0x42, 0x14, 0x42, 0x0C, 0x00, 0x42, 0x1C, 0xFF, 0x42, 0xFF,
+        ];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.x = 0x01;
+        let before = cpu.registers();
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.ip, 0x800E);
+        assert_eq!(cpu.cycles, 2 + 2 + 3 + 4 + 4 + 5);
+        assert_eq!(cpu.registers().flags, before.flags);
+        assert_eq!(cpu.a, before.a);
+    }
+
+    #[test]
+    fn jam_locks_up() {
+        // INX, JAM, INX
+        let program: Vec<u8> = vec![0xE8, 0x02, 0xE8, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0xFFFA] = 0x00;
+        cpu.bus[0xFFFB] = 0x90;
+        cpu.bus[0xFFFE] = 0x00;
+        cpu.bus[0xFFFF] = 0x90;
+
+        cpu.run_for_instructions(2).unwrap();
+        assert!(cpu.jammed);
+        assert!(!cpu.halted);
+        assert_eq!(cpu.ip, 0x8001);
+
+        // bounded runs stop straight away, while ticking lets time go by
+        // without either interrupt getting the CPU going again
+        cpu.irq(true);
+        cpu.nmi(true);
+        assert_eq!(cpu.run_for_cycles(20), Ok(StopReason::Jammed));
+        assert_eq!(cpu.run_until_pc(0x8003), Ok(StopReason::Jammed));
+        for _ in 0..10 {
+            cpu.tick();
+        }
+        assert_eq!(cpu.cycles, 2 + 2 + 10);
+        assert_eq!((cpu.ip, cpu.sp, cpu.x), (0x8001, 0xFD, 0x01));
+
+        cpu.irq(false);
+        cpu.reset();
+        assert!(!cpu.jammed);
+        cpu.ip = 0x8002;
+        cpu.run().unwrap();
+        assert_eq!(cpu.x, 0x02);
+    }
+
+    #[test]
+    fn run_returns_when_jammed() {
+        let mut cpu = Cpu6502::with_program(vec![0x02]);
+        cpu.run().unwrap();
+        assert!(cpu.jammed);
+        assert!(!cpu.halted);
+        assert_eq!(cpu.ip, 0x8000);
+
+        cpu.reset();
+        cpu.run_throttled(&mut Throttle::new(1_000_000.0)).unwrap();
+        assert!(cpu.jammed);
+        assert_eq!(cpu.ip, 0x8000);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+// How the unstable undocumented opcodes behave, which varies from chip to
+// chip and even with temperature. The defaults are the values most
+// commonly documented for them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnstableModel {
+    // Run the unstable opcodes at all. Turned off they are unimplemented,
+    // so a strict error policy catches programs that rely on them
+    pub enabled: bool,
+
+    // ANE (XAA) sets A to (A | ane_magic) & X & operand
+    pub ane_magic: u8,
+
+    // LXA sets A and X to (A | lxa_magic) & operand
+    pub lxa_magic: u8,
+
+    // SHA, SHX, SHY and TAS AND the value they store with the high byte of
+    // the address plus one. Some chips lose the AND when RDY is pulled low
+    // on the right cycle, which turning this off models
+    pub and_high_byte: bool,
+}
+
+impl Default for UnstableModel {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ane_magic: 0xEE,
+            lxa_magic: 0xEE,
+            and_high_byte: true,
+        }
+    }
+}
+
+// ANE
+pub fn and_x_magic<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    cpu.a = (cpu.a | cpu.unstable.ane_magic) & cpu.x & value;
+    cpu.flags.update_zero_and_negative(cpu.a);
+}
+
+// LXA
+pub fn load_a_and_x_magic<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    let result = (cpu.a | cpu.unstable.lxa_magic) & value;
+    cpu.a = result;
+    cpu.x = result;
+    cpu.flags.update_zero_and_negative(result);
+}
+
+// LAS: A, X and the stack pointer all become the operand ANDed with the
+// stack pointer
+pub fn load_and_stack<B: Bus>(cpu: &mut Cpu6502<B>, value: u8) {
+    let result = value & cpu.sp;
+    cpu.a = result;
+    cpu.x = result;
+    cpu.sp = result;
+    cpu.flags.update_zero_and_negative(result);
+}
+
+// The value SHA, SHX, SHY and TAS store, given the register and the high
+// byte of the address before indexing
+pub fn store_high_and<B: Bus>(cpu: &Cpu6502<B>, register: u8, high: u8) -> u8 {
+    if cpu.unstable.and_high_byte {
+        register & high.wrapping_add(1)
+    } else {
+        register
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn and_x_magic() {
+        // ANE #$FF
+        let program: Vec<u8> = vec![0x8B, 0xFF, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.a = 0x01;
+        cpu.x = 0x3F;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x2F);
+
+        cpu.reset();
+        cpu.unstable.ane_magic = 0xFF;
+        cpu.a = 0x01;
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x3F);
+    }
+
+    #[test]
+    fn load_a_and_x_magic() {
+        // LXA #$F0
+        let program: Vec<u8> = vec![0xAB, 0xF0, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.a = 0x01;
+        cpu.run().unwrap();
+        assert_eq!((cpu.a, cpu.x), (0xE0, 0xE0));
+        assert!(cpu.flags.negative);
+
+        cpu.reset();
+        cpu.unstable.lxa_magic = 0x00;
+        cpu.a = 0x01;
+        cpu.run().unwrap();
+        assert_eq!((cpu.a, cpu.x), (0x00, 0x00));
+        assert!(cpu.flags.zero);
+    }
+
+    #[test]
+    fn load_and_stack() {
+        // LAS $4200,Y across a page
+        let program: Vec<u8> = vec![0xBB, 0xFF, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0x4300] = 0xF3;
+        cpu.sp = 0x3F;
+        cpu.y = 0x01;
+        cpu.run().unwrap();
+
+        assert_eq!((cpu.a, cpu.x, cpu.sp), (0x33, 0x33, 0x33));
+        assert_eq!(cpu.cycles, 5);
+    }
+
+    #[test]
+    fn store_high_and() {
+        // SHX $4200,Y, SHY $4200,X, SHA ($10),Y, TAS $4220,Y
+        let program: Vec<u8> = vec![
+            0x9E, 0x00, 0x42, 0x9C, 0x00, 0x42, 0x93, 0x10, 0x9B, 0x20, 0x42, 0xFF,
+        ];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0x10] = 0x10;
+        cpu.bus[0x11] = 0x42;
+        cpu.a = 0xF3;
+        cpu.x = 0xFF;
+        cpu.y = 0x01;
+        cpu.run().unwrap();
+
+        // each stores its register ANDed with $43, and TAS also sets the
+        // stack pointer to A & X
+        assert_eq!(cpu.bus[0x4201], 0x43);
+        assert_eq!(cpu.bus[0x42FF], 0x01);
+        assert_eq!(cpu.bus[0x4211], 0x43);
+        assert_eq!(cpu.bus[0x4221], 0x43);
+        assert_eq!(cpu.sp, 0xF3);
+        assert_eq!(cpu.cycles, 5 + 5 + 6 + 5);
+    }
+
+    #[test]
+    fn store_high_and_across_a_page() {
+        // SHX $42FF,Y stores X & $43, and the high byte of the address is
+        // replaced by the value stored
+        let program: Vec<u8> = vec![0x9E, 0xFF, 0x42, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.x = 0x0F;
+        cpu.y = 0x01;
+        cpu.run().unwrap();
+
+        assert_eq!(cpu.bus[0x0300], 0x03);
+        assert_eq!(cpu.bus[0x4300], 0x00);
+
+        cpu.reset();
+        cpu.unstable.and_high_byte = false;
+        cpu.run().unwrap();
+        assert_eq!(cpu.bus[0x0F00], 0x0F);
+    }
+
+    #[test]
+    fn disabled() {
+        // a disabled unstable opcode is skipped under the lenient policy
+        let program: Vec<u8> = vec![0xAB, 0xEA, 0xFF];
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.unstable.enabled = false;
+        cpu.a = 0x69;
+        cpu.run().unwrap();
+
+        assert_eq!((cpu.a, cpu.x), (0x69, 0x00));
+        assert_eq!(cpu.cycles, 2);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::bus::Bus;
+use crate::cpu::microcode::Access;
+use crate::cpu::Cpu6502;
+
+// The signals on the outside of the chip. The CPU drives the address, R/W
+// and SYNC, and the data lines on write cycles, while the host drives the
+// data lines on read cycles along with the inputs. The interrupt and reset
+// inputs are active low on the real chip, but here true means asserted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pins {
+    pub address: u16,
+    pub data: u8,
+
+    // High for a read and low for a write
+    pub rw: bool,
+
+    // High while the CPU fetches an opcode
+    pub sync: bool,
+
+    // The clock, which each half cycle toggles
+    pub phi2: bool,
+
+    // Pulling RDY low stops the CPU on its next read cycle, which repeats
+    // until RDY goes high again. Write cycles carry on regardless
+    pub rdy: bool,
+
+    pub irq: bool,
+    pub nmi: bool,
+    pub res: bool,
+}
+
+impl Default for Pins {
+    // Ready, with nothing asserted and the clock high at the end of a cycle
+    fn default() -> Self {
+        Self {
+            address: 0,
+            data: 0,
+            rw: true,
+            sync: false,
+            phi2: true,
+            rdy: true,
+            irq: false,
+            nmi: false,
+            res: false,
+        }
+    }
+}
+
+impl<B: Bus> Cpu6502<B> {
+    // Runs half a clock cycle against the pins rather than the bus. Taking
+    // PHI2 low puts the address for the next cycle on the pins, along with
+    // the data for a write. The host then answers a read by setting the data
+    // lines, and taking PHI2 high latches them and finishes the cycle
+    pub fn half_cycle(&mut self, pins: &mut Pins) {
+        self.irq(pins.irq);
+        self.nmi(pins.nmi);
+
+        // While RES is held nothing runs, and the reset sequence starts
+        // once it's released
+        if pins.res {
+            self.res_line = true;
+            self.halted = false;
+            self.tasks.clear();
+            self.pin_cycle = None;
+            pins.rw = true;
+            pins.sync = false;
+            pins.phi2 = !pins.phi2;
+            return;
+        }
+
+        if pins.phi2 {
+            pins.phi2 = false;
+            self.drive_pins(pins);
+        } else {
+            pins.phi2 = true;
+            self.latch_pins(pins);
+        }
+    }
+
+    fn drive_pins(&mut self, pins: &mut Pins) {
+        if self.res_line {
+            self.res_line = false;
+            self.queue_reset();
+        }
+
+        // A cycle held up by RDY goes out again unchanged
+        let (task, sync) = match self.pin_cycle {
+            Some(cycle) => cycle,
+            None => {
+                let starting = self.tasks.is_empty();
+                let Some(task) = self.next_task() else {
+                    return;
+                };
+                (task, starting)
+            }
+        };
+
+        match self.access(task) {
+            Access::Read(addr) => {
+                pins.address = addr;
+                pins.rw = true;
+            }
+            Access::Write(addr, value) => {
+                pins.address = addr;
+                pins.data = value;
+                pins.rw = false;
+            }
+        }
+        pins.sync = sync;
+        self.pin_cycle = Some((task, sync));
+    }
+
+    fn latch_pins(&mut self, pins: &mut Pins) {
+        let Some((task, _)) = self.pin_cycle else {
+            return;
+        };
+
+        if pins.rw && !pins.rdy {
+            return;
+        }
+
+        if !pins.rw {
+            self.check_exit_port(pins.address, pins.data);
+        }
+
+        self.pin_cycle = None;
+        self.complete(task, pins.data);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bus::Unconnected;
+    use crate::cpu::{CpuError, Trap};
+
+    // A board with nothing but 64 KiB of RAM wired straight to the pins,
+    // logging each cycle as a logic analyser would see it when PHI2 rises
+    struct Board {
+        cpu: Cpu6502<Unconnected>,
+        pins: Pins,
+        ram: Vec<u8>,
+        trace: Vec<(u16, u8, bool, bool)>,
+    }
+
+    impl Board {
+        fn new(program: &[u8]) -> Self {
+            let mut ram = vec![0; 0x10000];
+            ram[0x8000..0x8000 + program.len()].copy_from_slice(program);
+            ram[0xFFFC] = 0x00;
+            ram[0xFFFD] = 0x80;
+
+            let mut cpu = Cpu6502::with_bus(Unconnected);
+            cpu.ip = 0x8000;
+
+            Self {
+                cpu,
+                pins: Pins::default(),
+                ram,
+                trace: Vec::new(),
+            }
+        }
+
+        fn cycle(&mut self) {
+            self.cpu.half_cycle(&mut self.pins);
+            assert!(!self.pins.phi2);
+
+            let addr = self.pins.address as usize;
+            if self.pins.rw {
+                self.pins.data = self.ram[addr];
+            } else {
+                self.ram[addr] = self.pins.data;
+            }
+            self.trace.push((
+                self.pins.address,
+                self.pins.data,
+                self.pins.rw,
+                self.pins.sync,
+            ));
+
+            self.cpu.half_cycle(&mut self.pins);
+            assert!(self.pins.phi2);
+        }
+    }
+
+    #[test]
+    fn runs_from_the_pins() {
+        // LDA #$69, STA $0200
+        let mut board = Board::new(&[0xA9, 0x69, 0x8D, 0x00, 0x02]);
+        for _ in 0..6 {
+            board.cycle();
+        }
+
+        assert_eq!(board.cpu.a, 0x69);
+        assert_eq!(board.ram[0x0200], 0x69);
+        assert_eq!(board.cpu.cycles, 6);
+        assert_eq!(
+            board.trace,
+            vec![
+                (0x8000, 0xA9, true, true),
+                (0x8001, 0x69, true, false),
+                (0x8002, 0x8D, true, true),
+                (0x8003, 0x00, true, false),
+                (0x8004, 0x02, true, false),
+                (0x0200, 0x69, false, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn rdy_stretches_reads() {
+        // LDA $0200 with RDY pulled low during the operand read
+        let mut board = Board::new(&[0xAD, 0x00, 0x02]);
+        board.ram[0x0200] = 0x69;
+        for _ in 0..3 {
+            board.cycle();
+        }
+
+        board.pins.rdy = false;
+        board.cycle();
+        board.cycle();
+        assert_eq!(board.cpu.a, 0x00);
+        assert_eq!(board.cpu.cycles, 3);
+
+        board.pins.rdy = true;
+        board.cycle();
+        assert_eq!(board.cpu.a, 0x69);
+        assert_eq!(board.cpu.cycles, 4);
+        assert_eq!(
+            &board.trace[3..],
+            &[
+                (0x0200, 0x69, true, false),
+                (0x0200, 0x69, true, false),
+                (0x0200, 0x69, true, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn rdy_does_not_stop_writes() {
+        // PHA with RDY pulled low for its write cycle, then NOP
+        let mut board = Board::new(&[0x48, 0xEA]);
+        board.cpu.a = 0x69;
+        board.cpu.sp = 0xFD;
+        board.cycle();
+        board.cycle();
+
+        board.pins.rdy = false;
+        board.cycle();
+        assert_eq!(board.ram[0x01FD], 0x69);
+        assert_eq!(board.cpu.cycles, 3);
+
+        // the next opcode fetch is a read, so that's where it stops
+        board.cycle();
+        board.cycle();
+        assert_eq!(board.cpu.cycles, 3);
+        assert_eq!(board.trace[3], (0x8001, 0xEA, true, true));
+        assert_eq!(board.trace[4], (0x8001, 0xEA, true, true));
+    }
+
+    #[test]
+    fn irq_from_the_pins() {
+        let mut board = Board::new(&[0xEA, 0xEA]);
+        board.ram[0xFFFE] = 0x00;
+        board.ram[0xFFFF] = 0x90;
+        board.cpu.sp = 0xFD;
+        board.pins.irq = true;
+
+        for _ in 0..7 {
+            board.cycle();
+        }
+        assert_eq!(board.cpu.ip, 0x9000);
+        assert!(board.cpu.flags.interrupt_disable);
+
+        // SYNC is high for the opcode fetch that the interrupt replaces
+        assert_eq!(board.trace[0], (0x8000, 0xEA, true, true));
+        assert_eq!(board.trace[4], (0x01FB, 0b0010_0000, false, false));
+    }
+
+    #[test]
+    fn exit_port_from_the_pins() {
+        // INC $F000, exiting with the result rather than the dummy write
+        let mut board = Board::new(&[0xEE, 0x00, 0xF0, 0xE8]);
+        board.cpu.traps.exit_port = Some(0xF000);
+        board.ram[0xF000] = 0x41;
+        for _ in 0..10 {
+            board.cycle();
+        }
+
+        assert_eq!(board.cpu.trap, Some(Trap::Exit(0x42)));
+        assert_eq!(board.ram[0xF000], 0x42);
+        assert_eq!(board.cpu.cycles, 6);
+        assert!(board.cpu.at_instruction_boundary());
+    }
+
+    #[test]
+    fn reset_from_the_pins() {
+        let mut board = Board::new(&[0xA9, 0x69]);
+        board.cpu.ip = 0x1234;
+
+        // none of this survives the reset, so the NMI is never taken
+        board.cpu.nmi_pending = true;
+        board.cpu.halted = true;
+        board.cpu.jammed = true;
+        board.cpu.trap = Some(Trap::Halt);
+        board.cpu.error = Some(CpuError::BusFault { address: 0x1234 });
+
+        board.pins.res = true;
+        board.cycle();
+        board.cycle();
+        assert_eq!(board.cpu.cycles, 0);
+
+        board.pins.res = false;
+        for _ in 0..7 {
+            board.cycle();
+        }
+        assert_eq!(board.cpu.ip, 0x8000);
+        assert_eq!(board.cpu.sp, 0xFD);
+        assert!(board.cpu.flags.interrupt_disable);
+        assert!(!board.cpu.nmi_pending);
+        assert!(!board.cpu.halted);
+        assert!(!board.cpu.jammed);
+        assert_eq!(board.cpu.trap, None);
+        assert_eq!(board.cpu.error, None);
+
+        // the pushes of the sequence read from the stack instead
+        let stack: Vec<_> = board.trace[4..7].iter().map(|cycle| cycle.0).collect();
+        assert_eq!(stack, vec![0x0100, 0x01FF, 0x01FE]);
+
+        board.cycle();
+        board.cycle();
+        assert_eq!(board.cpu.a, 0x69);
+    }
+}
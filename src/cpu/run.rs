@@ -0,0 +1,231 @@
+use crate::bus::Bus;
+use crate::cpu::{Cpu6502, CpuError, Trap};
+
+// Why a bounded run came to a stop. Halted is for a CPU that was halted
+// by hand rather than by a trap, and Jammed for one locked up by a JAM
+// opcode until reset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Trapped(Trap),
+    Halted,
+    Jammed,
+    CyclesElapsed,
+    InstructionsRun,
+    ReachedPc,
+    Condition,
+}
+
+impl StopReason {
+    pub fn exit_code(&self) -> Option<u8> {
+        match self {
+            StopReason::Trapped(Trap::Exit(code)) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+impl<B: Bus> Cpu6502<B> {
+    // Runs whole instructions until at least the given number of cycles
+    // have gone by, so the last one may overshoot the budget. A budget too
+    // large to count up to is as good as no budget at all
+    pub fn run_for_cycles(&mut self, cycles: u64) -> Result<StopReason, CpuError> {
+        let end = self.cycles.saturating_add(cycles);
+        self.run_while(|cpu| (cpu.cycles >= end).then_some(StopReason::CyclesElapsed))
+    }
+
+    pub fn run_for_instructions(&mut self, count: u64) -> Result<StopReason, CpuError> {
+        let mut remaining = count;
+        self.run_while(|_| {
+            if remaining == 0 {
+                return Some(StopReason::InstructionsRun);
+            }
+
+            remaining -= 1;
+            None
+        })
+    }
+
+    // Stops before running the instruction at the given address, which is
+    // straight away if the CPU is already there
+    pub fn run_until_pc(&mut self, pc: u16) -> Result<StopReason, CpuError> {
+        self.run_while(|cpu| (cpu.ip == pc).then_some(StopReason::ReachedPc))
+    }
+
+    // Stops before the first instruction the predicate returns true for
+    pub fn run_until(
+        &mut self,
+        mut predicate: impl FnMut(&Self) -> bool,
+    ) -> Result<StopReason, CpuError> {
+        self.run_while(|cpu| predicate(cpu).then_some(StopReason::Condition))
+    }
+
+    // Checks for a reason to stop between instructions, and whether the CPU
+    // has halted, jammed or hit an error
+    fn run_while(
+        &mut self,
+        mut stop: impl FnMut(&Self) -> Option<StopReason>,
+    ) -> Result<StopReason, CpuError> {
+        self.finish_ticked_instruction()?;
+
+        loop {
+            if self.halted {
+                return Ok(match self.trap {
+                    Some(trap) => StopReason::Trapped(trap),
+                    None => StopReason::Halted,
+                });
+            }
+
+            if self.jammed {
+                return Ok(StopReason::Jammed);
+            }
+
+            if let Some(reason) = stop(self) {
+                return Ok(reason);
+            }
+
+            self.run_instruction();
+            self.take_error()?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // INX forever
+    fn counting() -> Cpu6502 {
+        Cpu6502::with_program(vec![0xE8, 0x4C, 0x00, 0x80])
+    }
+
+    #[test]
+    fn run_for_cycles() {
+        let mut cpu = counting();
+
+        // INX and JMP take five cycles together
+        assert_eq!(cpu.run_for_cycles(50).unwrap(), StopReason::CyclesElapsed);
+        assert_eq!(cpu.cycles, 50);
+        assert_eq!(cpu.x, 10);
+
+        // the instruction that crosses the budget still runs to the end
+        assert_eq!(cpu.run_for_cycles(1).unwrap(), StopReason::CyclesElapsed);
+        assert_eq!(cpu.cycles, 52);
+        assert_eq!(cpu.x, 11);
+    }
+
+    #[test]
+    fn run_for_all_the_cycles() {
+        let mut cpu = Cpu6502::with_program(vec![0xE8, 0xE8, 0xFF]);
+
+        assert_eq!(cpu.run_for_cycles(1).unwrap(), StopReason::CyclesElapsed);
+        assert_eq!(
+            cpu.run_for_cycles(u64::MAX).unwrap(),
+            StopReason::Trapped(Trap::Halt)
+        );
+        assert_eq!(cpu.x, 2);
+    }
+
+    #[test]
+    fn run_for_instructions() {
+        let mut cpu = counting();
+
+        assert_eq!(
+            cpu.run_for_instructions(7).unwrap(),
+            StopReason::InstructionsRun
+        );
+        assert_eq!(cpu.x, 4);
+        assert_eq!(cpu.ip, 0x8001);
+
+        assert_eq!(
+            cpu.run_for_instructions(0).unwrap(),
+            StopReason::InstructionsRun
+        );
+        assert_eq!(cpu.ip, 0x8001);
+    }
+
+    #[test]
+    fn run_until_pc() {
+        let mut cpu = counting();
+
+        assert_eq!(cpu.run_until_pc(0x8001).unwrap(), StopReason::ReachedPc);
+        assert_eq!(cpu.x, 1);
+        assert_eq!(cpu.run_until_pc(0x8001).unwrap(), StopReason::ReachedPc);
+        assert_eq!(cpu.x, 1);
+
+        cpu.run_for_instructions(1).unwrap();
+        assert_eq!(cpu.run_until_pc(0x8001).unwrap(), StopReason::ReachedPc);
+        assert_eq!(cpu.x, 2);
+    }
+
+    #[test]
+    fn run_until() {
+        let mut cpu = counting();
+
+        assert_eq!(
+            cpu.run_until(|cpu| cpu.x == 0x42).unwrap(),
+            StopReason::Condition
+        );
+        assert_eq!(cpu.x, 0x42);
+
+        // the predicate can keep its own state
+        let mut seen = 0;
+        let reason = cpu.run_until(|_| {
+            seen += 1;
+            seen > 3
+        });
+        assert_eq!(reason, Ok(StopReason::Condition));
+        assert_eq!(seen, 4);
+    }
+
+    #[test]
+    fn stops_when_jammed() {
+        // INX, JAM
+        let mut cpu = Cpu6502::with_program(vec![0xE8, 0x02]);
+
+        assert_eq!(cpu.run_for_cycles(1_000).unwrap(), StopReason::Jammed);
+        assert_eq!(cpu.x, 1);
+        assert_eq!(cpu.ip, 0x8001);
+
+        let cycles = cpu.cycles;
+        assert_eq!(cpu.run_for_instructions(5).unwrap(), StopReason::Jammed);
+        assert_eq!(cpu.run_until_pc(0x9000).unwrap(), StopReason::Jammed);
+        assert_eq!(cpu.run_until(|_| false).unwrap(), StopReason::Jammed);
+        assert_eq!(cpu.cycles, cycles);
+    }
+
+    #[test]
+    fn stops_on_a_trap_from_a_ticked_instruction() {
+        // INC $F000, INX
+        let mut cpu = Cpu6502::with_program(vec![0xEE, 0x00, 0xF0, 0xE8]);
+        cpu.traps.exit_port = Some(0xF000);
+        cpu.bus[0xF000] = 0x41;
+
+        // ticked as far as the write of the unchanged value
+        for _ in 0..5 {
+            cpu.tick();
+        }
+        assert!(!cpu.halted);
+
+        let reason = cpu.run_for_instructions(10).unwrap();
+        assert_eq!(reason, StopReason::Trapped(Trap::Exit(0x42)));
+        assert_eq!(cpu.bus[0xF000], 0x42);
+        assert_eq!(cpu.cycles, 6);
+        assert_eq!(cpu.x, 0);
+        assert!(cpu.at_instruction_boundary());
+    }
+
+    #[test]
+    fn stops_when_halted() {
+        let mut cpu = Cpu6502::with_program(vec![0xE8, 0xE8, 0xFF]);
+
+        let reason = cpu.run_for_cycles(1_000).unwrap();
+        assert_eq!(reason, StopReason::Trapped(Trap::Halt));
+        assert_eq!(cpu.x, 2);
+        assert_eq!(cpu.run_until_pc(0x9000).unwrap(), reason);
+        assert_eq!(cpu.x, 2);
+
+        cpu.reset();
+        cpu.halted = true;
+        assert_eq!(cpu.run_until_pc(0x9000).unwrap(), StopReason::Halted);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::bus::Bus;
+use crate::cpu::instruction::{AddressingMode, Mnemonic};
+use crate::cpu::{Cpu6502, CpuError, Flags};
+
+// The programmer-visible state of the CPU at one point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Registers {
+    pub a: u8,
+    pub x: u8,
+    pub y: u8,
+    pub sp: u8,
+    pub ip: u16,
+    pub flags: Flags,
+}
+
+// What a single call to step did. Opcodes that trapped or weren't run have
+// no mnemonic or addressing mode, and the effective address is the
+// operand's address, jump target or branch target for instructions that
+// have one. A page is crossed when indexing carries into the high byte of
+// the address, or a branch target is on a different page than the next
+// instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub address: u16,
+    pub bytes: Vec<u8>,
+    pub mnemonic: Option<Mnemonic>,
+    pub mode: Option<AddressingMode>,
+    pub effective_address: Option<u16>,
+    pub page_crossed: bool,
+    pub cycles: u64,
+    pub before: Registers,
+    pub after: Registers,
+}
+
+impl Step {
+    // The flags the instruction set or cleared
+    pub fn changed_flags(&self) -> Flags {
+        self.before.flags.changed(&self.after.flags)
+    }
+}
+
+impl<B: Bus> Cpu6502<B> {
+    pub fn registers(&self) -> Registers {
+        Registers {
+            a: self.a,
+            x: self.x,
+            y: self.y,
+            sp: self.sp,
+            ip: self.ip,
+            flags: self.flags,
+        }
+    }
+
+    // Runs exactly one instruction, whether or not the CPU has halted. An
+    // interrupt that is waiting is taken first, in which case its cycles
+    // are counted too and the instruction is the first of the handler
+    pub fn step(&mut self) -> Result<Step, CpuError> {
+        self.finish_ticked_instruction()?;
+
+        let before = self.registers();
+        let cycles = self.cycles;
+
+        let address = self.run_instruction();
+        self.take_error()?;
+
+        let bytes = self.fetched[..self.fetched_len].to_vec();
+        let instruction = self.executed;
+        Ok(Step {
+            address,
+            bytes,
+            mnemonic: instruction.map(|instruction| instruction.mnemonic),
+            mode: instruction.map(|instruction| instruction.mode),
+            effective_address: self.resolved.map(|resolved| resolved.address),
+            page_crossed: self.resolved.is_some_and(|resolved| resolved.page_crossed),
+            cycles: self.cycles - cycles,
+            before,
+            after: self.registers(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn step_load() {
+        // LDA $4200,X across a page
+        let mut cpu = Cpu6502::with_program(vec![0xBD, 0xFF, 0x42]);
+        cpu.bus[0x4300] = 0x80;
+        cpu.x = 0x01;
+
+        let step = cpu.step().unwrap();
+        assert_eq!(step.address, 0x8000);
+        assert_eq!(step.bytes, vec![0xBD, 0xFF, 0x42]);
+        assert_eq!(step.mnemonic, Some(Mnemonic::Lda));
+        assert_eq!(step.mode, Some(AddressingMode::AbsoluteX));
+        assert_eq!(step.effective_address, Some(0x4300));
+        assert!(step.page_crossed);
+        assert_eq!(step.cycles, 5);
+
+        assert_eq!(step.before.a, 0x00);
+        assert_eq!(step.before.ip, 0x8000);
+        assert!(!step.before.flags.negative);
+        assert_eq!(step.after.a, 0x80);
+        assert_eq!(step.after.ip, 0x8003);
+        assert!(step.after.flags.negative);
+        assert_eq!(step.changed_flags().to_string(), "Nv-bdizc");
+        assert_eq!(step.after, cpu.registers());
+    }
+
+    #[test]
+    fn step_one_at_a_time() {
+        // LDA #$69, STA ($10),Y, INX, halt, INX
+        let mut cpu = Cpu6502::with_program(vec![0xA9, 0x69, 0x91, 0x10, 0xE8, 0xFF, 0xE8]);
+        cpu.bus[0x0010] = 0x00;
+        cpu.bus[0x0011] = 0x02;
+        cpu.y = 0x04;
+
+        let step = cpu.step().unwrap();
+        assert_eq!(step.bytes, vec![0xA9, 0x69]);
+        assert_eq!(step.mode, Some(AddressingMode::Immediate));
+        assert_eq!(step.effective_address, None);
+        assert_eq!(step.cycles, 2);
+
+        let step = cpu.step().unwrap();
+        assert_eq!(step.mnemonic, Some(Mnemonic::Sta));
+        assert_eq!(step.effective_address, Some(0x0204));
+        assert!(!step.page_crossed);
+        assert_eq!(step.cycles, 6);
+        assert_eq!(cpu.bus[0x0204], 0x69);
+
+        let step = cpu.step().unwrap();
+        assert_eq!(step.bytes, vec![0xE8]);
+        assert_eq!(step.mode, Some(AddressingMode::Implied));
+        assert_eq!((step.before.x, step.after.x), (0x00, 0x01));
+
+        // the halt opcode stops run, but step carries on past it
+        let step = cpu.step().unwrap();
+        assert_eq!(step.mnemonic, None);
+        assert!(cpu.halted);
+        cpu.step().unwrap();
+        assert_eq!(cpu.x, 0x02);
+    }
+
+    #[test]
+    fn step_jumps_and_branches() {
+        // JMP ($0200), then BNE -$10 at the target
+        let mut program = vec![0x6C, 0x00, 0x02];
+        program.resize(0x20, 0xEA);
+        program[0x10] = 0xD0;
+        program[0x11] = 0xF0;
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0x0200] = 0x10;
+        cpu.bus[0x0201] = 0x80;
+
+        let step = cpu.step().unwrap();
+        assert_eq!(step.mode, Some(AddressingMode::Indirect));
+        assert_eq!(step.effective_address, Some(0x8010));
+        assert_eq!(step.after.ip, 0x8010);
+
+        let step = cpu.step().unwrap();
+        assert_eq!(step.mnemonic, Some(Mnemonic::Bne));
+        assert_eq!(step.effective_address, Some(0x8002));
+        assert!(!step.page_crossed);
+        assert_eq!(step.cycles, 3);
+        assert_eq!(step.after.ip, 0x8002);
+    }
+
+    #[test]
+    fn step_takes_interrupts_first() {
+        let mut program = vec![0xEA; 0x12];
+        program[0x10] = 0xE8; // INX
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0xFFFE] = 0x10;
+        cpu.bus[0xFFFF] = 0x80;
+        cpu.irq(true);
+
+        let step = cpu.step().unwrap();
+        assert_eq!(step.before.ip, 0x8000);
+        assert_eq!(step.address, 0x8010);
+        assert_eq!(step.mnemonic, Some(Mnemonic::Inx));
+        assert_eq!(step.cycles, 9);
+    }
+
+    #[test]
+    fn step_reports_page_crossings() {
+        // LDA ($FF),Y takes its pointer from $FF and $00, then crosses
+        // from $42F0 to $4300. BCC then lands on its own page and the next
+        let mut program = vec![0xB1, 0xFF];
+        program.resize(0x100, 0xEA);
+        program[0x80] = 0x90;
+        program[0x81] = 0x80;
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.bus[0x00FF] = 0xF0;
+        cpu.bus[0x0000] = 0x42;
+        cpu.bus[0x4300] = 0x69;
+        cpu.y = 0x10;
+
+        let step = cpu.step().unwrap();
+        assert_eq!(step.effective_address, Some(0x4300));
+        assert!(step.page_crossed);
+        assert_eq!(step.cycles, 6);
+        assert_eq!(cpu.a, 0x69);
+
+        cpu.ip = 0x8080;
+        let step = cpu.step().unwrap();
+        assert_eq!(step.effective_address, Some(0x8002));
+        assert!(!step.page_crossed);
+
+        cpu.ip = 0x80F0;
+        cpu.bus[0x80F0] = 0x90;
+        cpu.bus[0x80F1] = 0x10;
+        let step = cpu.step().unwrap();
+        assert_eq!(step.effective_address, Some(0x8102));
+        assert!(step.page_crossed);
+        assert_eq!(step.cycles, 4);
+    }
+}
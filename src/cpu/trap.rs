@@ -0,0 +1,256 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu6502;
+
+// The JAM (or KIL) opcodes, which lock up the NMOS 6502 until reset
+pub const JAM_OPCODES: [u8; 12] = [
+    0x02, 0x12, 0x22, 0x32, 0x42, 0x52, 0x62, 0x72, 0x92, 0xB2, 0xD2, 0xF2,
+];
+
+// Which events halt the CPU. Only the legacy $FF halt opcode is on by
+// default, and turning it off frees $FF up to be a normal opcode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrapPolicy {
+    // Halt on a JAM opcode
+    pub jam: bool,
+
+    // Halt on BRK instead of taking the interrupt
+    pub brk: bool,
+
+    // Halt on a jump or branch to itself, the way test suites such as
+    // Klaus Dormann's signal that they've passed or failed
+    pub jump_to_self: bool,
+
+    // Halt on a write to this address, with the value written as the
+    // exit code
+    pub exit_port: Option<u16>,
+
+    // Halt on opcode $FF
+    pub legacy_halt: bool,
+}
+
+impl Default for TrapPolicy {
+    fn default() -> Self {
+        Self {
+            jam: false,
+            brk: false,
+            jump_to_self: false,
+            exit_port: None,
+            legacy_halt: true,
+        }
+    }
+}
+
+// What halted the CPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trap {
+    Jam(u8),
+    Break,
+
+    // The address of the instruction that jumps to itself
+    JumpToSelf(u16),
+
+    Exit(u8),
+    Halt,
+}
+
+impl<B: Bus> Cpu6502<B> {
+    pub(super) fn trap(&mut self, trap: Trap) {
+        self.halted = true;
+        self.trap = Some(trap);
+    }
+
+    // Halts on the opcodes the policy traps, returning whether it did
+    pub(super) fn trap_opcode(&mut self, opcode: u8) -> bool {
+        let trap = match opcode {
+            0xFF if self.traps.legacy_halt => Trap::Halt,
+            0x00 if self.traps.brk => Trap::Break,
+            _ if self.traps.jam && JAM_OPCODES.contains(&opcode) => Trap::Jam(opcode),
+            _ => return false,
+        };
+
+        self.trap(trap);
+        true
+    }
+
+    // A write to the exit port halts the CPU once the instruction making it
+    // is done, so both engines finish the instruction and exit with its
+    // last write there, such as the result of an INC rather than its dummy
+    // write
+    pub(super) fn check_exit_port(&mut self, addr: u16, value: u8) {
+        if self.traps.exit_port == Some(addr) {
+            self.exit_code = Some(value);
+        }
+    }
+
+    pub(super) fn trap_exit(&mut self) {
+        if let Some(code) = self.exit_code.take() {
+            self.trap(Trap::Exit(code));
+        }
+    }
+
+    pub(super) fn check_jump_to_self(&mut self, address: u16) {
+        if self.traps.jump_to_self && !self.halted && !self.jammed && self.ip == address {
+            self.trap(Trap::JumpToSelf(address));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cpu::StopReason;
+
+    fn with_traps(program: Vec<u8>, traps: TrapPolicy) -> Cpu6502 {
+        let mut cpu = Cpu6502::with_program(program);
+        cpu.traps = traps;
+        cpu
+    }
+
+    #[test]
+    fn legacy_halt() {
+        let mut cpu = Cpu6502::with_program(vec![0xE8, 0xFF, 0xE8]);
+        assert_eq!(
+            cpu.run_for_cycles(100).unwrap(),
+            StopReason::Trapped(Trap::Halt)
+        );
+        assert_eq!(cpu.trap, Some(Trap::Halt));
+        assert_eq!(cpu.x, 1);
+
+        // without it, $FF is ISC abs,X as on the real chip
+        let traps = TrapPolicy {
+            legacy_halt: false,
+            ..TrapPolicy::default()
+        };
+        let mut cpu = with_traps(vec![0xE8, 0xFF, 0x00, 0x02, 0xE8], traps);
+        cpu.run_for_instructions(3).unwrap();
+        assert!(!cpu.halted);
+        assert_eq!(cpu.x, 2);
+    }
+
+    #[test]
+    fn jam() {
+        for opcode in JAM_OPCODES {
+            let traps = TrapPolicy {
+                jam: true,
+                ..TrapPolicy::default()
+            };
+            let mut cpu = with_traps(vec![0xE8, opcode, 0xE8], traps);
+
+            let reason = cpu.run_for_cycles(100).unwrap();
+            assert_eq!(reason, StopReason::Trapped(Trap::Jam(opcode)));
+            assert_eq!(cpu.x, 1);
+        }
+    }
+
+    #[test]
+    fn brk() {
+        let traps = TrapPolicy {
+            brk: true,
+            ..TrapPolicy::default()
+        };
+        let mut cpu = with_traps(vec![0xE8, 0x00, 0xE8], traps);
+
+        assert_eq!(
+            cpu.run_for_cycles(100).unwrap(),
+            StopReason::Trapped(Trap::Break)
+        );
+        assert_eq!(cpu.ip, 0x8002);
+        assert_eq!(cpu.sp, 0xFD);
+    }
+
+    #[test]
+    fn jump_to_self() {
+        let traps = TrapPolicy {
+            jump_to_self: true,
+            ..TrapPolicy::default()
+        };
+
+        // count X down to zero, then BEQ *
+        let program = vec![0xA2, 0x03, 0xCA, 0xD0, 0xFD, 0xF0, 0xFE];
+        let mut cpu = with_traps(program, traps.clone());
+        let reason = cpu.run_for_cycles(1_000).unwrap();
+        assert_eq!(reason, StopReason::Trapped(Trap::JumpToSelf(0x8005)));
+        assert_eq!(cpu.x, 0);
+
+        let mut cpu = with_traps(vec![0xE8, 0x4C, 0x01, 0x80], traps);
+        cpu.run().unwrap();
+        assert_eq!(cpu.trap, Some(Trap::JumpToSelf(0x8001)));
+        assert_eq!(cpu.x, 1);
+    }
+
+    #[test]
+    fn exit_port() {
+        let traps = TrapPolicy {
+            exit_port: Some(0xF000),
+            ..TrapPolicy::default()
+        };
+
+        // LDA #$2A, STA $F000, INX
+        let program = vec![0xA9, 0x2A, 0x8D, 0x00, 0xF0, 0xE8];
+        let mut cpu = with_traps(program, traps);
+        let reason = cpu.run_for_cycles(100).unwrap();
+        assert_eq!(reason, StopReason::Trapped(Trap::Exit(0x2A)));
+        assert_eq!(reason.exit_code(), Some(0x2A));
+        assert_eq!(cpu.bus[0xF000], 0x2A);
+        assert_eq!(cpu.x, 0);
+    }
+
+    #[test]
+    fn exit_port_read_modify_write() {
+        let traps = TrapPolicy {
+            exit_port: Some(0xF000),
+            ..TrapPolicy::default()
+        };
+
+        // INC $F000 writes $41 back before the $42 it exits with
+        let program = vec![0xEE, 0x00, 0xF0, 0xE8];
+        let mut whole = with_traps(program.clone(), traps.clone());
+        whole.bus[0xF000] = 0x41;
+        whole.run().unwrap();
+
+        let mut ticked = with_traps(program, traps);
+        ticked.bus[0xF000] = 0x41;
+        for _ in 0..10 {
+            ticked.tick();
+        }
+
+        for cpu in [&whole, &ticked] {
+            assert_eq!(cpu.trap, Some(Trap::Exit(0x42)));
+            assert_eq!(cpu.bus[0xF000], 0x42);
+            assert_eq!(cpu.cycles, 6);
+            assert_eq!(cpu.x, 0);
+            assert!(cpu.at_instruction_boundary());
+        }
+    }
+
+    #[test]
+    fn traps_when_ticking() {
+        let traps = TrapPolicy {
+            jam: true,
+            jump_to_self: true,
+            exit_port: Some(0xF000),
+            ..TrapPolicy::default()
+        };
+
+        for (program, trap) in [
+            (vec![0x02], Trap::Jam(0x02)),
+            (vec![0x4C, 0x00, 0x80], Trap::JumpToSelf(0x8000)),
+            (vec![0x8D, 0x00, 0xF0], Trap::Exit(0x00)),
+        ] {
+            let mut cpu = with_traps(program, traps.clone());
+            for _ in 0..10 {
+                cpu.tick();
+            }
+            assert_eq!(cpu.trap, Some(trap));
+        }
+
+        let traps = TrapPolicy {
+            brk: true,
+            ..TrapPolicy::default()
+        };
+        let mut cpu = with_traps(vec![0x00], traps);
+        cpu.tick();
+        assert_eq!(cpu.trap, Some(Trap::Break));
+        assert_eq!(cpu.sp, 0xFD);
+    }
+}
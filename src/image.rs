@@ -0,0 +1,139 @@
+use crate::cpu::{IRQ_VECTOR, NMI_VECTOR, RESET_VECTOR};
+
+// A chunk of bytes to be placed at a fixed address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub origin: u16,
+    pub data: Vec<u8>,
+}
+
+// A memory image made of any number of segments, such as code, data and
+// vectors, plus an optional entry point to start at instead of following
+// the reset vector
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Image {
+    pub segments: Vec<Segment>,
+    pub entry: Option<u16>,
+}
+
+impl Image {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn segment(mut self, origin: u16, data: impl Into<Vec<u8>>) -> Self {
+        let data = data.into();
+        assert!(
+            origin as usize + data.len() <= 0x10000,
+            "segment at ${:04X} runs past $FFFF",
+            origin
+        );
+
+        self.segments.push(Segment { origin, data });
+        self
+    }
+
+    // Adds a segment holding the NMI, reset and IRQ/BRK vectors
+    pub fn vectors(self, nmi: u16, reset: u16, irq: u16) -> Self {
+        let mut data = Vec::with_capacity(6);
+        data.extend_from_slice(&nmi.to_le_bytes());
+        data.extend_from_slice(&reset.to_le_bytes());
+        data.extend_from_slice(&irq.to_le_bytes());
+        self.segment(NMI_VECTOR, data)
+    }
+
+    // Adds a segment holding only the reset vector
+    pub fn reset_vector(self, reset: u16) -> Self {
+        self.segment(RESET_VECTOR, reset.to_le_bytes())
+    }
+
+    // Adds a segment holding only the IRQ/BRK vector
+    pub fn irq_vector(self, irq: u16) -> Self {
+        self.segment(IRQ_VECTOR, irq.to_le_bytes())
+    }
+
+    pub fn entry(mut self, entry: u16) -> Self {
+        self.entry = Some(entry);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cpu::Cpu6502;
+
+    #[test]
+    fn load_segments() {
+        let image = Image::new()
+            .segment(0x0200, [0xA5, 0x10, 0xFF])
+            .segment(0x0010, [0x69])
+            .reset_vector(0x0200);
+
+        let mut cpu = Cpu6502::new();
+        cpu.load_image(&image);
+        assert_eq!(cpu.ip, 0x0200);
+
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn entry_point_leaves_vectors_alone() {
+        let image = Image::new()
+            .segment(0xC000, [0xA9, 0x69, 0xFF])
+            .entry(0xC000);
+
+        let mut cpu = Cpu6502::new();
+        cpu.bus[0xFFFC] = 0x34;
+        cpu.bus[0xFFFD] = 0x12;
+        cpu.load_image(&image);
+        assert_eq!(cpu.ip, 0xC000);
+        assert_eq!(cpu.bus[0xFFFC], 0x34);
+        assert_eq!(cpu.bus[0xFFFD], 0x12);
+
+        cpu.run().unwrap();
+        assert_eq!(cpu.a, 0x69);
+    }
+
+    #[test]
+    fn no_entry_point_follows_existing_reset_vector() {
+        let image = Image::new().segment(0x1234, [0xA9, 0x69, 0xFF]);
+
+        let mut cpu = Cpu6502::new();
+        cpu.bus[0xFFFC] = 0x34;
+        cpu.bus[0xFFFD] = 0x12;
+        cpu.load_image(&image);
+        assert_eq!(cpu.ip, 0x1234);
+    }
+
+    #[test]
+    fn vectors() {
+        let image = Image::new().vectors(0x1111, 0x2222, 0x3333);
+
+        let mut cpu = Cpu6502::new();
+        cpu.load_image(&image);
+        assert_eq!(cpu.bus[0xFFFA], 0x11);
+        assert_eq!(cpu.bus[0xFFFB], 0x11);
+        assert_eq!(cpu.bus[0xFFFC], 0x22);
+        assert_eq!(cpu.bus[0xFFFD], 0x22);
+        assert_eq!(cpu.bus[0xFFFE], 0x33);
+        assert_eq!(cpu.bus[0xFFFF], 0x33);
+        assert_eq!(cpu.ip, 0x2222);
+    }
+
+    #[test]
+    fn segment_up_to_top_of_memory() {
+        let image = Image::new().segment(0xFFFE, [0x42, 0x69]);
+
+        let mut cpu = Cpu6502::new();
+        cpu.load_image(&image);
+        assert_eq!(cpu.bus[0xFFFF], 0x69);
+    }
+
+    #[test]
+    #[should_panic]
+    fn segment_past_top_of_memory() {
+        let _ = Image::new().segment(0xFFFF, [0x42, 0x69]);
+    }
+}
@@ -1,2 +1,5 @@
+pub mod bus;
+pub mod clock;
 pub mod cpu;
+pub mod image;
 pub mod tasks;
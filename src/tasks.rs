@@ -1,12 +1,103 @@
-#[derive(Debug)]
+// One clock cycle of work for the cycle-stepped CPU. Every task performs
+// exactly one bus access, so an instruction is the list of tasks queued up
+// when its opcode is fetched. Tasks that find out mid-instruction that
+// another cycle is needed, such as a page crossing, queue it themselves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Task {
-    FetchByte,
+    // Reads the opcode at the instruction pointer and queues its tasks
+    FetchOpcode,
+
+    // Reads the byte at the instruction pointer and throws it away
+    ReadPc,
+
+    // Reads and throws away the byte at the instruction pointer, skipping
+    // over it
+    SkipByte,
+
+    // Reads the byte at the instruction pointer and throws it away, then
+    // runs an instruction that works on registers alone
+    ExecuteImplied,
+
+    // Reads the operand at the instruction pointer and runs the
+    // instruction on it
+    FetchImmediate,
+
+    // Reads a zero page address at the instruction pointer
+    FetchZeroPage,
+
+    // Read the two halves of an absolute address at the instruction
+    // pointer
     FetchLow,
     FetchHigh,
-    SetLow(u8),
-    SetHigh(u8),
-    AddLow(u8),
-    AddHigh(u8),
+
+    // Reads the high half of an absolute address and adds the index to
+    // the low half, leaving any carry to be fixed up by a later cycle
+    FetchHighIndexed(u8),
+
+    // Reads the high half of the target address and jumps to it
+    JumpHigh,
+
+    // Reads from the zero page address while adding the index to it,
+    // wrapping within page zero
+    IndexZeroPage(u8),
+
+    // Read the target address from the pointer. The high half comes from
+    // the next address on the same page, as the CPU never carries into the
+    // high byte here
+    ReadTargetLow,
+    ReadTargetHigh,
+    ReadTargetHighIndexed(u8),
+    JumpIndirect,
+
+    // Reads from the address before the carry from indexing has been added.
+    // For reads this is the real read when nothing carried, otherwise it is
+    // a dummy read and the read is queued again at the fixed address
+    ReadIndexed,
+    FixHigh,
+
+    // Reads the operand at the pointer and runs the instruction on it
     MemoryRead,
+
+    // Read-modify-write: reads the operand, writes it back unchanged while
+    // working out the result, then writes the result
+    ReadModify,
+    WriteBack,
+    WriteResult,
+
+    // Writes the register stored by the instruction to the pointer
     MemoryWrite,
+
+    // Reads the branch offset and queues the extra cycles if it's taken
+    FetchBranchOffset,
+    BranchTaken,
+    BranchFixHigh,
+
+    // Reads from the top of the stack without pulling anything
+    ReadStack,
+
+    // Stack pushes write the status with the break bit set, as BRK and PHP
+    // do, while a hardware interrupt pushes it with the break bit clear
+    Push(Register),
+    PushInterruptStatus,
+    Pull(Register),
+
+    // Reads from the top of the stack and moves the stack pointer down as
+    // if pushing, which is how the reset sequence treats its pushes
+    SuppressedPush,
+
+    // Reads the byte after a return address pulled by RTS and steps past it
+    IncrementPc,
+
+    // Read the interrupt vector, setting the interrupt disable flag
+    ReadVectorLow(u16),
+    ReadVectorHigh(u16),
+}
+
+// What a stack task pushes or pulls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    A,
+    Status,
+    PcLow,
+    PcHigh,
 }